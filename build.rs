@@ -1,41 +1,94 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use ethers_contract::Abigen;
 
+//bindings for every abi/*.json go to OUT_DIR, src/abi/mod.rs pulls in the generated abi.rs
 fn main() {
-    Abigen::new("MevShareCTFSimple", "./abi/mev_share_ctf_simple.json")
-        .unwrap()
-        .generate()
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    //the directory too so added files are picked up
+    println!("cargo:rerun-if-changed=abi");
+    let mut files: Vec<PathBuf> = fs::read_dir("abi")
         .unwrap()
-        .write_to_file("./src/abi/mev_share_ctf_simple.rs")
-        .unwrap();
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
 
-    Abigen::new("MevShareCTFTriple", "./abi/mev_share_ctf_triple.json")
-        .unwrap()
-        .generate()
-        .unwrap()
-        .write_to_file("./src/abi/mev_share_ctf_triple.rs")
-        .unwrap();
+    let mut contracts = Vec::new();
+    for path in &files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+        let name = contract_name(path, &stem);
+        Abigen::new(&name, path.to_str().unwrap())
+            .unwrap()
+            .generate()
+            .unwrap()
+            .write_to_file(out_dir.join(format!("{}.rs", stem)))
+            .unwrap();
+        contracts.push((stem, name));
+    }
+    fs::write(out_dir.join("abi.rs"), abi_module(&contracts)).unwrap();
+}
 
-    Abigen::new(
-        "MevShareMagicNumber",
-        "./abi/mev_share_magic_number_v3.json",
-    )
-    .unwrap()
-    .generate()
-    .unwrap()
-    .write_to_file("./src/abi/mev_share_magic_number_v3.rs")
-    .unwrap();
+/// `contractName` of an artifact, otherwise the file stem in pascal case.
+fn contract_name(path: &Path, stem: &str) -> String {
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    if let Some(name) = json.get("contractName").and_then(|name| name.as_str()) {
+        return name.to_string();
+    }
+    stem.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
 
-    Abigen::new("MevShareNewContracts", "./abi/mev_share_new_contracts.json")
-        .unwrap()
-        .generate()
-        .unwrap()
-        .write_to_file("./src/abi/mev_share_new_contracts.rs")
+/// A module per binding named after its file, plus `Contract` listing all of them.
+fn abi_module(contracts: &[(String, String)]) -> String {
+    let mut out = String::from("// generated by build.rs from abi/*.json\n");
+    for (stem, _) in contracts {
+        writeln!(
+            out,
+            "#[allow(clippy::module_inception)]\npub mod {stem} {{\n    include!(concat!(env!(\"OUT_DIR\"), \"/{stem}.rs\"));\n}}"
+        )
         .unwrap();
+    }
 
-    Abigen::new("MevShareNewContract", "./abi/mev_share_new_contract.json")
-        .unwrap()
-        .generate()
-        .unwrap()
-        .write_to_file("./src/abi/mev_share_new_contract.rs")
+    out.push_str("\n/// Every contract with an ABI in `abi/`.\n");
+    out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum Contract {\n");
+    for (_, name) in contracts {
+        writeln!(out, "    {name},").unwrap();
+    }
+    out.push_str("}\n\nimpl Contract {\n    pub const ALL: &'static [Contract] = &[\n");
+    for (_, name) in contracts {
+        writeln!(out, "        Contract::{name},").unwrap();
+    }
+    out.push_str("    ];\n\n");
+    out.push_str("    /// File stem of its ABI in `abi/`, also the name of its module.\n");
+    out.push_str("    pub fn file_stem(&self) -> &'static str {\n        match self {\n");
+    for (stem, name) in contracts {
+        writeln!(out, "            Contract::{name} => \"{stem}\",").unwrap();
+    }
+    out.push_str("        }\n    }\n\n");
+    out.push_str("    /// ABI compiled in at build time.\n");
+    out.push_str(
+        "    pub fn abi(&self) -> &'static ::ethers::core::abi::Abi {\n        match self {\n",
+    );
+    for (stem, name) in contracts {
+        writeln!(
+            out,
+            "            Contract::{name} => &{stem}::{}_ABI,",
+            name.to_uppercase()
+        )
         .unwrap();
+    }
+    out.push_str("        }\n    }\n}\n");
+    out
 }
@@ -0,0 +1,14 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Calls `claimReward()` on a challenge several times in one tx, all or nothing.
+/// abi/claim_executor.json holds the abi and this logic assembled by hand, no constructor args
+/// and no storage so it stays a few dozen bytes.
+contract ClaimExecutor {
+    function claimRewards(address target, uint256 times) external {
+        for (; times != 0; times--) {
+            (bool ok, ) = target.call(abi.encodeWithSelector(0xb88a802f));
+            require(ok);
+        }
+    }
+}
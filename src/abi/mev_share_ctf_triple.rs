@@ -1,686 +0,0 @@
-pub use mev_share_ctf_triple::*;
-/// This module was auto-generated with ethers-rs Abigen.
-/// More information at: <https://github.com/gakonst/ethers-rs>
-#[allow(
-    clippy::enum_variant_names,
-    clippy::too_many_arguments,
-    clippy::upper_case_acronyms,
-    clippy::type_complexity,
-    dead_code,
-    non_camel_case_types,
-)]
-pub mod mev_share_ctf_triple {
-    #[allow(deprecated)]
-    fn __abi() -> ::ethers::core::abi::Abi {
-        ::ethers::core::abi::ethabi::Contract {
-            constructor: ::core::option::Option::Some(::ethers::core::abi::ethabi::Constructor {
-                inputs: ::std::vec![
-                    ::ethers::core::abi::ethabi::Param {
-                        name: ::std::borrow::ToOwned::to_owned("_mevShareCaptureLogger"),
-                        kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                        internal_type: ::core::option::Option::Some(
-                            ::std::borrow::ToOwned::to_owned(
-                                "contract MevShareCaptureLogger",
-                            ),
-                        ),
-                    },
-                ],
-            }),
-            functions: ::core::convert::From::from([
-                (
-                    ::std::borrow::ToOwned::to_owned("activateRewardTriple"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned(
-                                "activateRewardTriple",
-                            ),
-                            inputs: ::std::vec![],
-                            outputs: ::std::vec![],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::Payable,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("activeBlock"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("activeBlock"),
-                            inputs: ::std::vec![],
-                            outputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::string::String::new(),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Uint(
-                                        256usize,
-                                    ),
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("uint256"),
-                                    ),
-                                },
-                            ],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("call"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("call"),
-                            inputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::borrow::ToOwned::to_owned("destination"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("address"),
-                                    ),
-                                },
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::borrow::ToOwned::to_owned("value"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Uint(
-                                        256usize,
-                                    ),
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("uint256"),
-                                    ),
-                                },
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::borrow::ToOwned::to_owned("data"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Bytes,
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("bytes"),
-                                    ),
-                                },
-                            ],
-                            outputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::string::String::new(),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Bool,
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("bool"),
-                                    ),
-                                },
-                            ],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("claimReward"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("claimReward"),
-                            inputs: ::std::vec![],
-                            outputs: ::std::vec![],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("owner"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("owner"),
-                            inputs: ::std::vec![],
-                            outputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::string::String::new(),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("address"),
-                                    ),
-                                },
-                            ],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::View,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("renounceOwnership"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("renounceOwnership"),
-                            inputs: ::std::vec![],
-                            outputs: ::std::vec![],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("transferOwnership"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Function {
-                            name: ::std::borrow::ToOwned::to_owned("transferOwnership"),
-                            inputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::Param {
-                                    name: ::std::borrow::ToOwned::to_owned("newOwner"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                                    internal_type: ::core::option::Option::Some(
-                                        ::std::borrow::ToOwned::to_owned("address"),
-                                    ),
-                                },
-                            ],
-                            outputs: ::std::vec![],
-                            constant: ::core::option::Option::None,
-                            state_mutability: ::ethers::core::abi::ethabi::StateMutability::NonPayable,
-                        },
-                    ],
-                ),
-            ]),
-            events: ::core::convert::From::from([
-                (
-                    ::std::borrow::ToOwned::to_owned("Activate"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Event {
-                            name: ::std::borrow::ToOwned::to_owned("Activate"),
-                            inputs: ::std::vec![],
-                            anonymous: false,
-                        },
-                    ],
-                ),
-                (
-                    ::std::borrow::ToOwned::to_owned("OwnershipTransferred"),
-                    ::std::vec![
-                        ::ethers::core::abi::ethabi::Event {
-                            name: ::std::borrow::ToOwned::to_owned(
-                                "OwnershipTransferred",
-                            ),
-                            inputs: ::std::vec![
-                                ::ethers::core::abi::ethabi::EventParam {
-                                    name: ::std::borrow::ToOwned::to_owned("previousOwner"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                                    indexed: true,
-                                },
-                                ::ethers::core::abi::ethabi::EventParam {
-                                    name: ::std::borrow::ToOwned::to_owned("newOwner"),
-                                    kind: ::ethers::core::abi::ethabi::ParamType::Address,
-                                    indexed: true,
-                                },
-                            ],
-                            anonymous: false,
-                        },
-                    ],
-                ),
-            ]),
-            errors: ::std::collections::BTreeMap::new(),
-            receive: false,
-            fallback: false,
-        }
-    }
-    ///The parsed JSON ABI of the contract.
-    pub static MEVSHARECTFTRIPLE_ABI: ::ethers::contract::Lazy<
-        ::ethers::core::abi::Abi,
-    > = ::ethers::contract::Lazy::new(__abi);
-    pub struct MevShareCTFTriple<M>(::ethers::contract::Contract<M>);
-    impl<M> ::core::clone::Clone for MevShareCTFTriple<M> {
-        fn clone(&self) -> Self {
-            Self(::core::clone::Clone::clone(&self.0))
-        }
-    }
-    impl<M> ::core::ops::Deref for MevShareCTFTriple<M> {
-        type Target = ::ethers::contract::Contract<M>;
-        fn deref(&self) -> &Self::Target {
-            &self.0
-        }
-    }
-    impl<M> ::core::ops::DerefMut for MevShareCTFTriple<M> {
-        fn deref_mut(&mut self) -> &mut Self::Target {
-            &mut self.0
-        }
-    }
-    impl<M> ::core::fmt::Debug for MevShareCTFTriple<M> {
-        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-            f.debug_tuple(::core::stringify!(MevShareCTFTriple))
-                .field(&self.address())
-                .finish()
-        }
-    }
-    impl<M: ::ethers::providers::Middleware> MevShareCTFTriple<M> {
-        /// Creates a new contract instance with the specified `ethers` client at
-        /// `address`. The contract derefs to a `ethers::Contract` object.
-        pub fn new<T: Into<::ethers::core::types::Address>>(
-            address: T,
-            client: ::std::sync::Arc<M>,
-        ) -> Self {
-            Self(
-                ::ethers::contract::Contract::new(
-                    address.into(),
-                    MEVSHARECTFTRIPLE_ABI.clone(),
-                    client,
-                ),
-            )
-        }
-        ///Calls the contract's `activateRewardTriple` (0x83cf5e37) function
-        pub fn activate_reward_triple(
-            &self,
-        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
-            self.0
-                .method_hash([131, 207, 94, 55], ())
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `activeBlock` (0x96b81609) function
-        pub fn active_block(
-            &self,
-        ) -> ::ethers::contract::builders::ContractCall<M, ::ethers::core::types::U256> {
-            self.0
-                .method_hash([150, 184, 22, 9], ())
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `call` (0x6dbf2fa0) function
-        pub fn call(
-            &self,
-            destination: ::ethers::core::types::Address,
-            value: ::ethers::core::types::U256,
-            data: ::ethers::core::types::Bytes,
-        ) -> ::ethers::contract::builders::ContractCall<M, bool> {
-            self.0
-                .method_hash([109, 191, 47, 160], (destination, value, data))
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `claimReward` (0xb88a802f) function
-        pub fn claim_reward(&self) -> ::ethers::contract::builders::ContractCall<M, ()> {
-            self.0
-                .method_hash([184, 138, 128, 47], ())
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `owner` (0x8da5cb5b) function
-        pub fn owner(
-            &self,
-        ) -> ::ethers::contract::builders::ContractCall<
-            M,
-            ::ethers::core::types::Address,
-        > {
-            self.0
-                .method_hash([141, 165, 203, 91], ())
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `renounceOwnership` (0x715018a6) function
-        pub fn renounce_ownership(
-            &self,
-        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
-            self.0
-                .method_hash([113, 80, 24, 166], ())
-                .expect("method not found (this should never happen)")
-        }
-        ///Calls the contract's `transferOwnership` (0xf2fde38b) function
-        pub fn transfer_ownership(
-            &self,
-            new_owner: ::ethers::core::types::Address,
-        ) -> ::ethers::contract::builders::ContractCall<M, ()> {
-            self.0
-                .method_hash([242, 253, 227, 139], new_owner)
-                .expect("method not found (this should never happen)")
-        }
-        ///Gets the contract's `Activate` event
-        pub fn activate_filter(
-            &self,
-        ) -> ::ethers::contract::builders::Event<
-            ::std::sync::Arc<M>,
-            M,
-            ActivateFilter,
-        > {
-            self.0.event()
-        }
-        ///Gets the contract's `OwnershipTransferred` event
-        pub fn ownership_transferred_filter(
-            &self,
-        ) -> ::ethers::contract::builders::Event<
-            ::std::sync::Arc<M>,
-            M,
-            OwnershipTransferredFilter,
-        > {
-            self.0.event()
-        }
-        /// Returns an `Event` builder for all the events of this contract.
-        pub fn events(
-            &self,
-        ) -> ::ethers::contract::builders::Event<
-            ::std::sync::Arc<M>,
-            M,
-            MevShareCTFTripleEvents,
-        > {
-            self.0.event_with_filter(::core::default::Default::default())
-        }
-    }
-    impl<M: ::ethers::providers::Middleware> From<::ethers::contract::Contract<M>>
-    for MevShareCTFTriple<M> {
-        fn from(contract: ::ethers::contract::Contract<M>) -> Self {
-            Self::new(contract.address(), contract.client())
-        }
-    }
-    #[derive(
-        Clone,
-        ::ethers::contract::EthEvent,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethevent(name = "Activate", abi = "Activate()")]
-    pub struct ActivateFilter;
-    #[derive(
-        Clone,
-        ::ethers::contract::EthEvent,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethevent(
-        name = "OwnershipTransferred",
-        abi = "OwnershipTransferred(address,address)"
-    )]
-    pub struct OwnershipTransferredFilter {
-        #[ethevent(indexed)]
-        pub previous_owner: ::ethers::core::types::Address,
-        #[ethevent(indexed)]
-        pub new_owner: ::ethers::core::types::Address,
-    }
-    ///Container type for all of the contract's events
-    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
-    pub enum MevShareCTFTripleEvents {
-        ActivateFilter(ActivateFilter),
-        OwnershipTransferredFilter(OwnershipTransferredFilter),
-    }
-    impl ::ethers::contract::EthLogDecode for MevShareCTFTripleEvents {
-        fn decode_log(
-            log: &::ethers::core::abi::RawLog,
-        ) -> ::core::result::Result<Self, ::ethers::core::abi::Error> {
-            if let Ok(decoded) = ActivateFilter::decode_log(log) {
-                return Ok(MevShareCTFTripleEvents::ActivateFilter(decoded));
-            }
-            if let Ok(decoded) = OwnershipTransferredFilter::decode_log(log) {
-                return Ok(MevShareCTFTripleEvents::OwnershipTransferredFilter(decoded));
-            }
-            Err(::ethers::core::abi::Error::InvalidData)
-        }
-    }
-    impl ::core::fmt::Display for MevShareCTFTripleEvents {
-        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-            match self {
-                Self::ActivateFilter(element) => ::core::fmt::Display::fmt(element, f),
-                Self::OwnershipTransferredFilter(element) => {
-                    ::core::fmt::Display::fmt(element, f)
-                }
-            }
-        }
-    }
-    impl ::core::convert::From<ActivateFilter> for MevShareCTFTripleEvents {
-        fn from(value: ActivateFilter) -> Self {
-            Self::ActivateFilter(value)
-        }
-    }
-    impl ::core::convert::From<OwnershipTransferredFilter> for MevShareCTFTripleEvents {
-        fn from(value: OwnershipTransferredFilter) -> Self {
-            Self::OwnershipTransferredFilter(value)
-        }
-    }
-    ///Container type for all input parameters for the `activateRewardTriple` function with signature `activateRewardTriple()` and selector `0x83cf5e37`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "activateRewardTriple", abi = "activateRewardTriple()")]
-    pub struct ActivateRewardTripleCall;
-    ///Container type for all input parameters for the `activeBlock` function with signature `activeBlock()` and selector `0x96b81609`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "activeBlock", abi = "activeBlock()")]
-    pub struct ActiveBlockCall;
-    ///Container type for all input parameters for the `call` function with signature `call(address,uint256,bytes)` and selector `0x6dbf2fa0`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "call", abi = "call(address,uint256,bytes)")]
-    pub struct CallCall {
-        pub destination: ::ethers::core::types::Address,
-        pub value: ::ethers::core::types::U256,
-        pub data: ::ethers::core::types::Bytes,
-    }
-    ///Container type for all input parameters for the `claimReward` function with signature `claimReward()` and selector `0xb88a802f`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "claimReward", abi = "claimReward()")]
-    pub struct ClaimRewardCall;
-    ///Container type for all input parameters for the `owner` function with signature `owner()` and selector `0x8da5cb5b`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "owner", abi = "owner()")]
-    pub struct OwnerCall;
-    ///Container type for all input parameters for the `renounceOwnership` function with signature `renounceOwnership()` and selector `0x715018a6`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "renounceOwnership", abi = "renounceOwnership()")]
-    pub struct RenounceOwnershipCall;
-    ///Container type for all input parameters for the `transferOwnership` function with signature `transferOwnership(address)` and selector `0xf2fde38b`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthCall,
-        ::ethers::contract::EthDisplay,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    #[ethcall(name = "transferOwnership", abi = "transferOwnership(address)")]
-    pub struct TransferOwnershipCall {
-        pub new_owner: ::ethers::core::types::Address,
-    }
-    ///Container type for all of the contract's call
-    #[derive(Clone, ::ethers::contract::EthAbiType, Debug, PartialEq, Eq, Hash)]
-    pub enum MevShareCTFTripleCalls {
-        ActivateRewardTriple(ActivateRewardTripleCall),
-        ActiveBlock(ActiveBlockCall),
-        Call(CallCall),
-        ClaimReward(ClaimRewardCall),
-        Owner(OwnerCall),
-        RenounceOwnership(RenounceOwnershipCall),
-        TransferOwnership(TransferOwnershipCall),
-    }
-    impl ::ethers::core::abi::AbiDecode for MevShareCTFTripleCalls {
-        fn decode(
-            data: impl AsRef<[u8]>,
-        ) -> ::core::result::Result<Self, ::ethers::core::abi::AbiError> {
-            let data = data.as_ref();
-            if let Ok(decoded)
-                = <ActivateRewardTripleCall as ::ethers::core::abi::AbiDecode>::decode(
-                    data,
-                ) {
-                return Ok(Self::ActivateRewardTriple(decoded));
-            }
-            if let Ok(decoded)
-                = <ActiveBlockCall as ::ethers::core::abi::AbiDecode>::decode(data) {
-                return Ok(Self::ActiveBlock(decoded));
-            }
-            if let Ok(decoded)
-                = <CallCall as ::ethers::core::abi::AbiDecode>::decode(data) {
-                return Ok(Self::Call(decoded));
-            }
-            if let Ok(decoded)
-                = <ClaimRewardCall as ::ethers::core::abi::AbiDecode>::decode(data) {
-                return Ok(Self::ClaimReward(decoded));
-            }
-            if let Ok(decoded)
-                = <OwnerCall as ::ethers::core::abi::AbiDecode>::decode(data) {
-                return Ok(Self::Owner(decoded));
-            }
-            if let Ok(decoded)
-                = <RenounceOwnershipCall as ::ethers::core::abi::AbiDecode>::decode(
-                    data,
-                ) {
-                return Ok(Self::RenounceOwnership(decoded));
-            }
-            if let Ok(decoded)
-                = <TransferOwnershipCall as ::ethers::core::abi::AbiDecode>::decode(
-                    data,
-                ) {
-                return Ok(Self::TransferOwnership(decoded));
-            }
-            Err(::ethers::core::abi::Error::InvalidData.into())
-        }
-    }
-    impl ::ethers::core::abi::AbiEncode for MevShareCTFTripleCalls {
-        fn encode(self) -> Vec<u8> {
-            match self {
-                Self::ActivateRewardTriple(element) => {
-                    ::ethers::core::abi::AbiEncode::encode(element)
-                }
-                Self::ActiveBlock(element) => {
-                    ::ethers::core::abi::AbiEncode::encode(element)
-                }
-                Self::Call(element) => ::ethers::core::abi::AbiEncode::encode(element),
-                Self::ClaimReward(element) => {
-                    ::ethers::core::abi::AbiEncode::encode(element)
-                }
-                Self::Owner(element) => ::ethers::core::abi::AbiEncode::encode(element),
-                Self::RenounceOwnership(element) => {
-                    ::ethers::core::abi::AbiEncode::encode(element)
-                }
-                Self::TransferOwnership(element) => {
-                    ::ethers::core::abi::AbiEncode::encode(element)
-                }
-            }
-        }
-    }
-    impl ::core::fmt::Display for MevShareCTFTripleCalls {
-        fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-            match self {
-                Self::ActivateRewardTriple(element) => {
-                    ::core::fmt::Display::fmt(element, f)
-                }
-                Self::ActiveBlock(element) => ::core::fmt::Display::fmt(element, f),
-                Self::Call(element) => ::core::fmt::Display::fmt(element, f),
-                Self::ClaimReward(element) => ::core::fmt::Display::fmt(element, f),
-                Self::Owner(element) => ::core::fmt::Display::fmt(element, f),
-                Self::RenounceOwnership(element) => ::core::fmt::Display::fmt(element, f),
-                Self::TransferOwnership(element) => ::core::fmt::Display::fmt(element, f),
-            }
-        }
-    }
-    impl ::core::convert::From<ActivateRewardTripleCall> for MevShareCTFTripleCalls {
-        fn from(value: ActivateRewardTripleCall) -> Self {
-            Self::ActivateRewardTriple(value)
-        }
-    }
-    impl ::core::convert::From<ActiveBlockCall> for MevShareCTFTripleCalls {
-        fn from(value: ActiveBlockCall) -> Self {
-            Self::ActiveBlock(value)
-        }
-    }
-    impl ::core::convert::From<CallCall> for MevShareCTFTripleCalls {
-        fn from(value: CallCall) -> Self {
-            Self::Call(value)
-        }
-    }
-    impl ::core::convert::From<ClaimRewardCall> for MevShareCTFTripleCalls {
-        fn from(value: ClaimRewardCall) -> Self {
-            Self::ClaimReward(value)
-        }
-    }
-    impl ::core::convert::From<OwnerCall> for MevShareCTFTripleCalls {
-        fn from(value: OwnerCall) -> Self {
-            Self::Owner(value)
-        }
-    }
-    impl ::core::convert::From<RenounceOwnershipCall> for MevShareCTFTripleCalls {
-        fn from(value: RenounceOwnershipCall) -> Self {
-            Self::RenounceOwnership(value)
-        }
-    }
-    impl ::core::convert::From<TransferOwnershipCall> for MevShareCTFTripleCalls {
-        fn from(value: TransferOwnershipCall) -> Self {
-            Self::TransferOwnership(value)
-        }
-    }
-    ///Container type for all return fields from the `activeBlock` function with signature `activeBlock()` and selector `0x96b81609`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthAbiType,
-        ::ethers::contract::EthAbiCodec,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    pub struct ActiveBlockReturn(pub ::ethers::core::types::U256);
-    ///Container type for all return fields from the `call` function with signature `call(address,uint256,bytes)` and selector `0x6dbf2fa0`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthAbiType,
-        ::ethers::contract::EthAbiCodec,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    pub struct CallReturn(pub bool);
-    ///Container type for all return fields from the `owner` function with signature `owner()` and selector `0x8da5cb5b`
-    #[derive(
-        Clone,
-        ::ethers::contract::EthAbiType,
-        ::ethers::contract::EthAbiCodec,
-        Default,
-        Debug,
-        PartialEq,
-        Eq,
-        Hash
-    )]
-    pub struct OwnerReturn(pub ::ethers::core::types::Address);
-}
@@ -1,5 +1,2 @@
-pub mod mev_share_ctf_simple;
-pub mod mev_share_ctf_triple;
-pub mod mev_share_magic_number_v3;
-pub mod mev_share_new_contracts;
-pub mod mev_share_new_contract;
\ No newline at end of file
+//bindings are generated into OUT_DIR by build.rs
+include!(concat!(env!("OUT_DIR"), "/abi.rs"));
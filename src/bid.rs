@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::{parse_ether, parse_units};
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use tracing::{info_span, Instrument};
+
+use crate::ctf::Flag;
+use crate::metrics;
+use crate::signer::BotSigner;
+use crate::wallet::Fees;
+
+/// Gas limit of the coinbase payment tx appended to a bundle.
+pub const COINBASE_TRANSFER_GAS: u64 = 50_000;
+
+/// How much to pay the builder for a challenge, amounts are decimal strings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BidStrategy {
+    /// same bid every time
+    Fixed {
+        priority_fee_gwei: Option<String>,
+        /// ether paid through `coinbase_payer`
+        coinbase_transfer: Option<String>,
+    },
+    /// `percent` of `expected_reward` ether, as priority fee or a coinbase transfer
+    Percentage {
+        expected_reward: String,
+        percent: f64,
+        #[serde(default)]
+        pay_via: PayVia,
+    },
+    /// priority fee that goes up by `step_gwei` every block we bid on the challenge again,
+    /// back to `start_gwei` once it has gone `reset_blocks` without a bid
+    Escalating {
+        start_gwei: String,
+        step_gwei: String,
+        max_gwei: String,
+        #[serde(default = "default_reset_blocks")]
+        reset_blocks: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayVia {
+    #[default]
+    PriorityFee,
+    CoinbaseTransfer,
+}
+
+fn default_reset_blocks() -> u64 {
+    10
+}
+
+/// What one bundle bids, `None` leaves the estimate from the rpc.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bid {
+    pub priority_fee: Option<U256>,
+    pub coinbase_transfer: Option<U256>,
+}
+
+impl Bid {
+    /// Extra txs, and nonces, the bid adds to a bundle.
+    pub fn extra_txs(&self) -> u64 {
+        self.coinbase_transfer.is_some() as u64
+    }
+
+    pub fn extra_gas(&self) -> u64 {
+        self.extra_txs() * COINBASE_TRANSFER_GAS
+    }
+}
+
+enum Strategy {
+    Fixed(Bid),
+    Percentage {
+        amount: U256,
+        pay_via: PayVia,
+    },
+    Escalating {
+        start: U256,
+        step: U256,
+        max: U256,
+        reset_blocks: u64,
+    },
+}
+
+/// Per challenge bids, looked up by `Flag::name` falling back to the `default` entry.
+pub struct BidEngine {
+    strategies: HashMap<String, Strategy>,
+    coinbase_payer: Option<Address>,
+    //challenge -> (last block bid on, escalation step)
+    escalation: Mutex<HashMap<&'static str, (U64, u64)>>,
+}
+
+impl BidEngine {
+    pub fn new(
+        bids: &HashMap<String, BidStrategy>,
+        coinbase_payer: Option<Address>,
+    ) -> Result<Self> {
+        let mut strategies = HashMap::new();
+        for (challenge, strategy) in bids {
+            let strategy =
+                Strategy::parse(strategy).map_err(|e| eyre!("bids.{}: {}", challenge, e))?;
+            if strategy.pays_coinbase() && coinbase_payer.is_none() {
+                return Err(eyre!(
+                    "bids.{} pays a coinbase transfer but coinbase_payer is not set",
+                    challenge
+                ));
+            }
+            strategies.insert(challenge.clone(), strategy);
+        }
+        Ok(Self {
+            strategies,
+            coinbase_payer,
+            escalation: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Bid for a bundle of `gas` targeting `block`.
+    pub fn bid(&self, flag: &Flag, block: U64, gas: u64) -> Bid {
+        let strategy = match self
+            .strategies
+            .get(flag.name())
+            .or_else(|| self.strategies.get("default"))
+        {
+            Some(strategy) => strategy,
+            None => return Bid::default(),
+        };
+        match strategy {
+            Strategy::Fixed(bid) => *bid,
+            Strategy::Percentage { amount, pay_via } => match pay_via {
+                PayVia::PriorityFee => Bid {
+                    priority_fee: Some(*amount / U256::from(gas.max(1))),
+                    coinbase_transfer: None,
+                },
+                PayVia::CoinbaseTransfer => Bid {
+                    priority_fee: None,
+                    coinbase_transfer: Some(*amount),
+                },
+            },
+            Strategy::Escalating {
+                start,
+                step,
+                max,
+                reset_blocks,
+            } => {
+                let mut escalation = self.escalation.lock().unwrap();
+                let steps = match escalation.get(flag.name()) {
+                    //several bundles for the same block bid the same
+                    Some((last, steps)) if *last == block => *steps,
+                    Some((last, steps))
+                        if block > *last && block - *last <= (*reset_blocks).into() =>
+                    {
+                        steps + 1
+                    }
+                    _ => 0,
+                };
+                escalation.insert(flag.name(), (block, steps));
+                Bid {
+                    priority_fee: Some((*start + *step * steps).min(*max)),
+                    coinbase_transfer: None,
+                }
+            }
+        }
+    }
+
+    /// Signed payment of the bid's coinbase transfer, if it has one.
+    pub async fn coinbase_transfer_tx<M: Middleware>(
+        &self,
+        bid: &Bid,
+        client: &M,
+        tx_signer: &BotSigner,
+        nonce: u64,
+        fees: Fees,
+    ) -> Result<Option<Bytes>>
+    where
+        M::Error: 'static,
+    {
+        let (value, payer) = match (bid.coinbase_transfer, self.coinbase_payer) {
+            (Some(value), Some(payer)) => (value, payer),
+            _ => return Ok(None),
+        };
+        //the payer contract forwards msg.value to block.coinbase
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(tx_signer.address())
+            .to(payer)
+            .value(value)
+            .gas(COINBASE_TRANSFER_GAS)
+            .max_fee_per_gas(fees.max_fee_per_gas)
+            .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+            .nonce(nonce)
+            .chain_id(tx_signer.chain_id())
+            .into();
+        metrics::time_rpc("fill_transaction", client.fill_transaction(&mut tx, None)).await?;
+        let signature = tx_signer
+            .sign_transaction(&tx)
+            .instrument(info_span!("sign_tx"))
+            .await?;
+        Ok(Some(tx.rlp_signed(&signature)))
+    }
+}
+
+impl Strategy {
+    fn parse(strategy: &BidStrategy) -> Result<Self> {
+        Ok(match strategy {
+            BidStrategy::Fixed {
+                priority_fee_gwei,
+                coinbase_transfer,
+            } => Strategy::Fixed(Bid {
+                priority_fee: priority_fee_gwei.as_deref().map(gwei).transpose()?,
+                coinbase_transfer: coinbase_transfer.as_deref().map(ether).transpose()?,
+            }),
+            BidStrategy::Percentage {
+                expected_reward,
+                percent,
+                pay_via,
+            } => {
+                if !(0.0..=100.0).contains(percent) {
+                    return Err(eyre!("percent must be between 0 and 100"));
+                }
+                //basis points keep the maths in integers
+                let amount = ether(expected_reward)? * U256::from((percent * 100.0) as u64)
+                    / U256::from(10_000);
+                Strategy::Percentage {
+                    amount,
+                    pay_via: *pay_via,
+                }
+            }
+            BidStrategy::Escalating {
+                start_gwei,
+                step_gwei,
+                max_gwei,
+                reset_blocks,
+            } => Strategy::Escalating {
+                start: gwei(start_gwei)?,
+                step: gwei(step_gwei)?,
+                max: gwei(max_gwei)?,
+                reset_blocks: *reset_blocks,
+            },
+        })
+    }
+
+    fn pays_coinbase(&self) -> bool {
+        match self {
+            Strategy::Fixed(bid) => bid.coinbase_transfer.is_some(),
+            Strategy::Percentage { pay_via, .. } => *pay_via == PayVia::CoinbaseTransfer,
+            Strategy::Escalating { .. } => false,
+        }
+    }
+}
+
+fn gwei(amount: &str) -> Result<U256> {
+    Ok(parse_units(amount, "gwei")
+        .map_err(|_| eyre!("{:?} is not a gwei amount", amount))?
+        .into())
+}
+
+fn ether(amount: &str) -> Result<U256> {
+    parse_ether(amount).map_err(|_| eyre!("{:?} is not an ether amount", amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy(toml: &str) -> BidStrategy {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn engine(bids: &[(&str, &str)], coinbase_payer: Option<Address>) -> Result<BidEngine> {
+        let bids = bids
+            .iter()
+            .map(|(challenge, toml)| (challenge.to_string(), strategy(toml)))
+            .collect();
+        BidEngine::new(&bids, coinbase_payer)
+    }
+
+    fn gwei_u(amount: u64) -> U256 {
+        U256::from(amount) * U256::exp10(9)
+    }
+
+    #[test]
+    fn fixed_bid() {
+        let engine = engine(
+            &[(
+                "default",
+                "strategy = \"fixed\"\npriority_fee_gwei = \"2.5\"",
+            )],
+            None,
+        )
+        .unwrap();
+        let bid = engine.bid(&Flag::CTFSimple, 100u64.into(), 21_000);
+        assert_eq!(bid.priority_fee, Some(U256::from(2_500_000_000u64)));
+        assert_eq!(bid.coinbase_transfer, None);
+        assert_eq!(bid.extra_txs(), 0);
+    }
+
+    #[test]
+    fn challenge_bid_overrides_default() {
+        let engine = engine(
+            &[
+                ("default", "strategy = \"fixed\"\npriority_fee_gwei = \"1\""),
+                (
+                    "CTFTriple",
+                    "strategy = \"fixed\"\npriority_fee_gwei = \"3\"",
+                ),
+            ],
+            None,
+        )
+        .unwrap();
+        let triple = engine.bid(&Flag::CTFTriple, 100u64.into(), 21_000);
+        let simple = engine.bid(&Flag::CTFSimple, 100u64.into(), 21_000);
+        assert_eq!(triple.priority_fee, Some(gwei_u(3)));
+        assert_eq!(simple.priority_fee, Some(gwei_u(1)));
+    }
+
+    #[test]
+    fn no_strategy_leaves_the_estimate() {
+        let engine = engine(&[], None).unwrap();
+        let bid = engine.bid(&Flag::CTFSimple, 100u64.into(), 21_000);
+        assert_eq!(bid.priority_fee, None);
+        assert_eq!(bid.coinbase_transfer, None);
+    }
+
+    #[test]
+    fn percentage_as_priority_fee_spreads_over_gas() {
+        let engine = engine(
+            &[(
+                "default",
+                "strategy = \"percentage\"\nexpected_reward = \"0.05\"\npercent = 50",
+            )],
+            None,
+        )
+        .unwrap();
+        //0.025 ether over 100k gas
+        let bid = engine.bid(&Flag::NewContracts, 100u64.into(), 100_000);
+        assert_eq!(bid.priority_fee, Some(gwei_u(250)));
+        assert_eq!(bid.coinbase_transfer, None);
+    }
+
+    #[test]
+    fn percentage_uses_basis_points() {
+        let engine = engine(
+            &[(
+                "default",
+                "strategy = \"percentage\"\nexpected_reward = \"1\"\npercent = 12.5\npay_via = \"coinbase_transfer\"",
+            )],
+            Some(Address::repeat_byte(1)),
+        )
+        .unwrap();
+        let bid = engine.bid(&Flag::CTFSimple, 100u64.into(), 21_000);
+        assert_eq!(bid.priority_fee, None);
+        assert_eq!(bid.coinbase_transfer, Some(parse_ether("0.125").unwrap()));
+        assert_eq!(bid.extra_txs(), 1);
+        assert_eq!(bid.extra_gas(), COINBASE_TRANSFER_GAS);
+    }
+
+    #[test]
+    fn percentage_out_of_range_is_rejected() {
+        let err = engine(
+            &[(
+                "default",
+                "strategy = \"percentage\"\nexpected_reward = \"1\"\npercent = 150",
+            )],
+            None,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("bids.default"));
+    }
+
+    #[test]
+    fn coinbase_transfer_needs_a_payer() {
+        let bids = [(
+            "CTFSimple",
+            "strategy = \"fixed\"\ncoinbase_transfer = \"0.01\"",
+        )];
+        assert!(engine(&bids, None).is_err());
+        assert!(engine(&bids, Some(Address::repeat_byte(1))).is_ok());
+    }
+
+    #[test]
+    fn escalating_steps_per_block_up_to_max() {
+        let engine = engine(
+            &[(
+                "default",
+                "strategy = \"escalating\"\nstart_gwei = \"2\"\nstep_gwei = \"1\"\nmax_gwei = \"4\"",
+            )],
+            None,
+        )
+        .unwrap();
+        let fee = |block: u64| {
+            engine
+                .bid(&Flag::CTFTriple, block.into(), 21_000)
+                .priority_fee
+                .unwrap()
+        };
+        assert_eq!(fee(100), gwei_u(2));
+        //several bundles for the same block bid the same
+        assert_eq!(fee(100), gwei_u(2));
+        assert_eq!(fee(101), gwei_u(3));
+        assert_eq!(fee(103), gwei_u(4));
+        assert_eq!(fee(104), gwei_u(4));
+    }
+
+    #[test]
+    fn escalating_resets_after_reset_blocks() {
+        let engine = engine(
+            &[(
+                "default",
+                "strategy = \"escalating\"\nstart_gwei = \"2\"\nstep_gwei = \"1\"\nmax_gwei = \"20\"\nreset_blocks = 5",
+            )],
+            None,
+        )
+        .unwrap();
+        let fee = |flag: Flag, block: u64| {
+            engine
+                .bid(&flag, block.into(), 21_000)
+                .priority_fee
+                .unwrap()
+        };
+        assert_eq!(fee(Flag::CTFTriple, 100), gwei_u(2));
+        //exactly reset_blocks later still escalates
+        assert_eq!(fee(Flag::CTFTriple, 105), gwei_u(3));
+        assert_eq!(fee(Flag::CTFTriple, 111), gwei_u(2));
+        //an older block starts over too
+        assert_eq!(fee(Flag::CTFTriple, 110), gwei_u(2));
+        //challenges escalate separately
+        assert_eq!(fee(Flag::CTFSimple, 111), gwei_u(2));
+    }
+
+    #[test]
+    fn parse_rejects_bad_amounts() {
+        for toml in [
+            "strategy = \"fixed\"\npriority_fee_gwei = \"two\"",
+            "strategy = \"fixed\"\ncoinbase_transfer = \"0.0.1\"",
+            "strategy = \"escalating\"\nstart_gwei = \"2\"\nstep_gwei = \"x\"\nmax_gwei = \"4\"",
+        ] {
+            assert!(Strategy::parse(&strategy(toml)).is_err(), "{}", toml);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use ethers::types::{Address, Bytes, H256, U64};
+use eyre::{eyre, Result};
+use mev_share::rpc::{
+    BundleItem, Privacy, PrivacyHint, Refund, RefundConfig, SendBundleRequest, Validity,
+};
+use serde::Deserialize;
+
+use crate::ctf::Flag;
+
+/// What a bundle may leak to other searchers, one of the MEV-Share hint names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    Calldata,
+    ContractAddress,
+    Logs,
+    FunctionSelector,
+    Hash,
+    TxHash,
+}
+
+impl FromStr for Hint {
+    type Err = eyre::Report;
+
+    /// By the same snake_case name config uses.
+    fn from_str(name: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string()))
+            .map_err(|_| eyre!("{:?} is not a hint", name))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RefundEntry {
+    /// bundle body item the refund is for, 0 is the backrun target
+    pub body_idx: u64,
+    pub percent: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RefundRecipient {
+    pub address: Address,
+    pub percent: u64,
+}
+
+/// How a solver with several alternative solutions sends them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bundling {
+    /// a bundle per candidate, all signed with the same nonce so at most one lands
+    #[default]
+    PerCandidate,
+    /// one bundle with every candidate on consecutive nonces, allowed to revert
+    Packed,
+}
+
+impl Bundling {
+    /// Nonces a solve with `candidates` alternatives needs.
+    pub fn nonces(&self, candidates: u64) -> u64 {
+        match self {
+            Bundling::PerCandidate => 1,
+            Bundling::Packed => candidates,
+        }
+    }
+
+    pub fn nonce_offset(&self, candidate: usize) -> u64 {
+        match self {
+            Bundling::PerCandidate => 0,
+            Bundling::Packed => candidate as u64,
+        }
+    }
+
+    pub fn solution(&self, tx: Bytes) -> Solution {
+        Solution {
+            tx,
+            can_revert: *self == Bundling::Packed,
+        }
+    }
+}
+
+/// A signed solver tx and whether its bundle may still land if it reverts.
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub tx: Bytes,
+    pub can_revert: bool,
+}
+
+impl Solution {
+    pub fn required(tx: Bytes) -> Self {
+        Self {
+            tx,
+            can_revert: false,
+        }
+    }
+}
+
+/// Privacy and validity fields of `mev_sendBundle` for one challenge, unset fields are left out.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BundleOptions {
+    pub hints: Option<Vec<Hint>>,
+    pub builders: Option<Vec<String>>,
+    pub refund: Option<Vec<RefundEntry>>,
+    pub refund_config: Option<Vec<RefundRecipient>>,
+    /// only used by solvers with alternative solutions, magic number and new contracts
+    pub bundling: Option<Bundling>,
+}
+
+impl BundleOptions {
+    /// Checks that do not depend on the bundle, for `Config::validate`.
+    pub fn problems(&self, name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(builders) = &self.builders {
+            if builders.is_empty() {
+                problems.push(format!(
+                    "{}.builders is empty, leave it out to use the default",
+                    name
+                ));
+            }
+            let mut seen = HashSet::new();
+            for builder in builders {
+                if builder.trim().is_empty() {
+                    problems.push(format!("{}.builders has an empty name", name));
+                } else if !seen.insert(builder) {
+                    problems.push(format!("{}.builders lists {} twice", name, builder));
+                }
+            }
+        }
+        if let Some(refund) = &self.refund {
+            check_percents(
+                &mut problems,
+                &format!("{}.refund", name),
+                refund.iter().map(|r| r.percent),
+            );
+        }
+        if let Some(recipients) = &self.refund_config {
+            check_percents(
+                &mut problems,
+                &format!("{}.refund_config", name),
+                recipients.iter().map(|r| r.percent),
+            );
+        }
+        problems
+    }
+
+    fn privacy(&self) -> Option<Privacy> {
+        if self.hints.is_none() && self.builders.is_none() {
+            return None;
+        }
+        Some(Privacy {
+            hints: self.hints.as_deref().map(privacy_hint),
+            builders: self.builders.clone(),
+        })
+    }
+
+    fn validity(&self) -> Option<Validity> {
+        if self.refund.is_none() && self.refund_config.is_none() {
+            return None;
+        }
+        Some(Validity {
+            refund: self.refund.as_ref().map(|refund| {
+                refund
+                    .iter()
+                    .map(|r| Refund {
+                        body_idx: r.body_idx,
+                        percent: r.percent,
+                    })
+                    .collect()
+            }),
+            refund_config: self.refund_config.as_ref().map(|recipients| {
+                recipients
+                    .iter()
+                    .map(|r| RefundConfig {
+                        address: r.address,
+                        percent: r.percent,
+                    })
+                    .collect()
+            }),
+        })
+    }
+}
+
+/// Options per challenge name, falling back to the `default` entry.
+#[derive(Debug, Clone, Default)]
+pub struct BundleSettings {
+    options: HashMap<String, BundleOptions>,
+}
+
+impl BundleSettings {
+    pub fn new(options: HashMap<String, BundleOptions>) -> Self {
+        Self { options }
+    }
+
+    fn options(&self, flag: &Flag) -> Option<&BundleOptions> {
+        self.options
+            .get(flag.name())
+            .or_else(|| self.options.get("default"))
+    }
+
+    pub fn bundling(&self, flag: &Flag) -> Bundling {
+        self.options(flag)
+            .and_then(|options| options.bundling)
+            .unwrap_or_default()
+    }
+
+    /// Fill in privacy and validity for `flag`, refusing options that do not fit this bundle.
+    pub fn apply(&self, flag: &Flag, bundle: &mut SendBundleRequest) -> Result<()> {
+        let Some(options) = self.options(flag) else {
+            return Ok(());
+        };
+        for refund in options.refund.iter().flatten() {
+            match bundle.bundle_body.get(refund.body_idx as usize) {
+                Some(BundleItem::Hash { .. }) => (),
+                Some(_) => {
+                    return Err(eyre!(
+                        "{} refund body_idx {} is not a backrun target",
+                        flag.name(),
+                        refund.body_idx
+                    ))
+                }
+                None => {
+                    return Err(eyre!(
+                        "{} refund body_idx {} is past the {} item bundle",
+                        flag.name(),
+                        refund.body_idx,
+                        bundle.bundle_body.len()
+                    ))
+                }
+            }
+        }
+        //whatever the solver set explicitly wins over the config
+        bundle.privacy = match (bundle.privacy.take(), options.privacy()) {
+            (Some(mut set), Some(default)) => {
+                set.hints = set.hints.or(default.hints);
+                set.builders = set.builders.or(default.builders);
+                Some(set)
+            }
+            (set, default) => set.or(default),
+        };
+        bundle.validity = bundle.validity.take().or_else(|| options.validity());
+        Ok(())
+    }
+}
+
+/// Builds a `mev_sendBundle` request, body items go in the order they are added.
+pub struct BundleBuilder {
+    bundle: SendBundleRequest,
+}
+
+impl BundleBuilder {
+    pub fn new(block: U64) -> Self {
+        let mut bundle = SendBundleRequest::default();
+        bundle.inclusion.block = block;
+        Self { bundle }
+    }
+
+    /// Last block the bundle may land in, it only targets `block` otherwise.
+    pub fn max_block(mut self, max_block: U64) -> Self {
+        self.bundle.inclusion.max_block = Some(max_block);
+        self
+    }
+
+    /// Backrun a pending tx, or another searcher's bundle, by the hash from its hint.
+    pub fn backrun(mut self, hash: H256) -> Self {
+        self.bundle.bundle_body.push(BundleItem::Hash { hash });
+        self
+    }
+
+    pub fn tx(mut self, tx: Bytes, can_revert: bool) -> Self {
+        self.bundle
+            .bundle_body
+            .push(BundleItem::Tx { tx, can_revert });
+        self
+    }
+
+    /// Put a whole earlier bundle in front of what follows so it only lands on top of it.
+    pub fn nest(mut self, bundle: SendBundleRequest) -> Self {
+        self.bundle.bundle_body.push(BundleItem::Bundle { bundle });
+        self
+    }
+
+    /// Share these hints about our own bundle so others can backrun it,
+    /// over whatever `BundleOptions::hints` sets for the challenge.
+    pub fn share(mut self, hints: &[Hint]) -> Self {
+        self.bundle
+            .privacy
+            .get_or_insert_with(Privacy::default)
+            .hints = Some(privacy_hint(hints));
+        self
+    }
+
+    /// Fails if the body is empty or a nested bundle cannot land in our block range.
+    pub fn build(self) -> Result<SendBundleRequest> {
+        let bundle = self.bundle;
+        if bundle.bundle_body.is_empty() {
+            return Err(eyre!("bundle has no body"));
+        }
+        let inclusion = &bundle.inclusion;
+        let last = inclusion.max_block.unwrap_or(inclusion.block);
+        for item in &bundle.bundle_body {
+            if let BundleItem::Bundle { bundle: inner } = item {
+                let inner_last = inner.inclusion.max_block.unwrap_or(inner.inclusion.block);
+                if inner.inclusion.block > inclusion.block || inner_last < last {
+                    return Err(eyre!(
+                        "nested bundle for blocks {}-{} does not cover {}-{}",
+                        inner.inclusion.block,
+                        inner_last,
+                        inclusion.block,
+                        last
+                    ));
+                }
+            }
+        }
+        Ok(bundle)
+    }
+}
+
+/// First backrun target, looking inside nested bundles.
+pub fn target(bundle: &SendBundleRequest) -> Option<H256> {
+    bundle.bundle_body.iter().find_map(|item| match item {
+        BundleItem::Hash { hash } => Some(*hash),
+        BundleItem::Bundle { bundle } => target(bundle),
+        BundleItem::Tx { .. } => None,
+    })
+}
+
+/// Every signed tx in body order, including those of nested bundles.
+pub fn txs(bundle: &SendBundleRequest) -> Vec<&Bytes> {
+    let mut txs = Vec::new();
+    for item in &bundle.bundle_body {
+        match item {
+            BundleItem::Tx { tx, .. } => txs.push(tx),
+            BundleItem::Bundle { bundle } => txs.extend(self::txs(bundle)),
+            BundleItem::Hash { .. } => (),
+        }
+    }
+    txs
+}
+
+fn privacy_hint(hints: &[Hint]) -> PrivacyHint {
+    hints
+        .iter()
+        .fold(PrivacyHint::default(), |hint, h| match h {
+            Hint::Calldata => hint.with_calldata(),
+            Hint::ContractAddress => hint.with_contract_address(),
+            Hint::Logs => hint.with_logs(),
+            Hint::FunctionSelector => hint.with_function_selector(),
+            Hint::Hash => hint.with_hash(),
+            Hint::TxHash => hint.with_tx_hash(),
+        })
+}
+
+fn check_percents(problems: &mut Vec<String>, name: &str, percents: impl Iterator<Item = u64>) {
+    let mut total = 0;
+    for percent in percents {
+        if percent == 0 || percent > 100 {
+            problems.push(format!("{} percent {} must be 1-100", name, percent));
+        }
+        total += percent;
+    }
+    if total > 100 {
+        problems.push(format!(
+            "{} percents add up to {}, more than 100",
+            name, total
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inner(block: u64, max_block: Option<u64>) -> SendBundleRequest {
+        let builder = BundleBuilder::new(block.into())
+            .backrun(H256::repeat_byte(1))
+            .tx(Bytes::from(vec![1]), false);
+        match max_block {
+            Some(max_block) => builder.max_block(max_block.into()),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    }
+
+    fn outer(block: u64, max_block: u64, nested: SendBundleRequest) -> Result<SendBundleRequest> {
+        BundleBuilder::new(block.into())
+            .max_block(max_block.into())
+            .nest(nested)
+            .tx(Bytes::from(vec![2]), false)
+            .build()
+    }
+
+    #[test]
+    fn nested_bundle_covering_our_blocks() {
+        let bundle = outer(100, 102, inner(99, Some(103))).unwrap();
+        assert!(matches!(bundle.bundle_body[0], BundleItem::Bundle { .. }));
+        assert_eq!(bundle.inclusion.max_block, Some(102u64.into()));
+        //the target and txs of the nested bundle come first
+        assert_eq!(target(&bundle), Some(H256::repeat_byte(1)));
+        assert_eq!(
+            txs(&bundle),
+            vec![&Bytes::from(vec![1]), &Bytes::from(vec![2])]
+        );
+    }
+
+    #[test]
+    fn nested_bundle_starting_later_is_refused() {
+        assert!(outer(100, 100, inner(101, Some(105))).is_err());
+    }
+
+    #[test]
+    fn nested_bundle_ending_earlier_is_refused() {
+        assert!(outer(100, 102, inner(100, Some(101))).is_err());
+        //no max_block means only its one block
+        assert!(outer(100, 101, inner(100, None)).is_err());
+        assert!(outer(100, 100, inner(100, None)).is_ok());
+    }
+
+    #[test]
+    fn empty_bundle_is_refused() {
+        assert!(BundleBuilder::new(100u64.into()).build().is_err());
+    }
+
+    #[test]
+    fn shared_hints() {
+        let bundle = BundleBuilder::new(100u64.into())
+            .backrun(H256::repeat_byte(1))
+            .tx(Bytes::from(vec![1]), false)
+            .share(&[Hint::Hash, Hint::Logs])
+            .build()
+            .unwrap();
+        let privacy = serde_json::to_value(&bundle.privacy).unwrap();
+        let hints = privacy["hints"].as_array().unwrap();
+        assert!(hints.contains(&"hash".into()));
+        assert!(hints.contains(&"logs".into()));
+        assert!(!hints.contains(&"calldata".into()));
+    }
+
+    #[test]
+    fn shared_hints_win_over_config() {
+        let settings = BundleSettings::new(HashMap::from([(
+            "default".to_string(),
+            BundleOptions {
+                hints: Some(vec![Hint::Calldata]),
+                builders: Some(vec!["flashbots".to_string()]),
+                ..Default::default()
+            },
+        )]));
+        let mut bundle = BundleBuilder::new(100u64.into())
+            .backrun(H256::repeat_byte(1))
+            .tx(Bytes::from(vec![1]), false)
+            .share(&[Hint::TxHash])
+            .build()
+            .unwrap();
+        settings.apply(&Flag::CTFSimple, &mut bundle).unwrap();
+        let privacy = serde_json::to_value(&bundle.privacy).unwrap();
+        let hints = privacy["hints"].as_array().unwrap();
+        assert!(hints.contains(&"tx_hash".into()));
+        assert!(!hints.contains(&"calldata".into()));
+        //what the solver left unset still comes from config
+        assert_eq!(privacy["builders"], serde_json::json!(["flashbots"]));
+    }
+
+    #[test]
+    fn hint_names() {
+        assert_eq!("logs".parse::<Hint>().unwrap(), Hint::Logs);
+        assert_eq!("tx_hash".parse::<Hint>().unwrap(), Hint::TxHash);
+        assert!("TxHash".parse::<Hint>().is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use ethers::prelude::*;
+use eyre::{eyre, Result};
+use tracing::{info, warn};
+
+use crate::ctf::Flag;
+use crate::deploy::DeployKind;
+use crate::metrics;
+use crate::store::Store;
+use crate::telemetry;
+
+//blocks per eth_getLogs call when backfilling
+const BACKFILL_CHUNK: u64 = 5_000;
+
+/// One flag, a challenge contract and for `MevShareNewContracts` the activation whose
+/// child was claimed, since the parent registers the captures of both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaptureKey {
+    pub contract: Address,
+    pub kind: Option<DeployKind>,
+}
+
+/// A capture logger log that credits one of our searchers with a challenge.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub contract: Address,
+    pub kind: Option<DeployKind>,
+    pub flag: Flag,
+    pub searcher: Address,
+    pub block: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+}
+
+impl Capture {
+    pub fn key(&self) -> CaptureKey {
+        CaptureKey {
+            contract: self.contract,
+            kind: self.kind,
+        }
+    }
+}
+
+/// Find the capture logger every challenge was constructed with. There is no getter so look for
+/// an address with code that all of them hold, either as an immutable in their code or in storage.
+pub async fn find_capture_logger(
+    client: &Arc<Provider<Ws>>,
+    contracts: &HashMap<Address, Flag>,
+) -> Result<Address> {
+    let mut common: Option<HashSet<Address>> = None;
+    for address in contracts.keys() {
+        let mut held = HashSet::new();
+        let code = metrics::time_rpc("get_code", client.get_code(*address, None)).await?;
+        held.extend(pushed_addresses(&code));
+        for slot in 0..8u64 {
+            let word = metrics::time_rpc(
+                "get_storage_at",
+                client.get_storage_at(*address, H256::from_low_u64_be(slot), None),
+            )
+            .await?;
+            if let Some(held_address) = as_address(word.as_bytes()) {
+                held.insert(held_address);
+            }
+        }
+        common = Some(match common {
+            Some(common) => common.intersection(&held).copied().collect(),
+            None => held,
+        });
+    }
+    //the owner is shared too, but it is not a contract
+    let mut loggers = Vec::new();
+    for candidate in common.unwrap_or_default() {
+        if contracts.contains_key(&candidate) {
+            continue;
+        }
+        let code = metrics::time_rpc("get_code", client.get_code(candidate, None)).await?;
+        if !code.is_empty() {
+            loggers.push(candidate);
+        }
+    }
+    match loggers[..] {
+        [logger] => Ok(logger),
+        [] => Err(eyre!(
+            "no capture logger shared by every challenge, set capture_logger"
+        )),
+        _ => Err(eyre!(
+            "several capture logger candidates {:?}, set capture_logger",
+            loggers
+        )),
+    }
+}
+
+/// Which registered challenges our searchers have captured, according to the capture logger.
+/// Persisted in the store so a restart does not go after flags we already have.
+pub struct CaptureTracker {
+    logger: Address,
+    contracts: HashMap<Address, Flag>,
+    searchers: HashSet<Address>,
+    captured: Mutex<HashSet<CaptureKey>>,
+    store: Arc<Store>,
+}
+
+impl CaptureTracker {
+    /// Load what the store already knows, backfill from `from_block` if set, then follow new logs.
+    pub async fn spawn(
+        client: Arc<Provider<Ws>>,
+        logger: Address,
+        contracts: HashMap<Address, Flag>,
+        searchers: Vec<Address>,
+        store: Arc<Store>,
+        from_block: Option<u64>,
+    ) -> Result<Arc<Self>> {
+        let captured = store
+            .captures()?
+            .into_iter()
+            .filter_map(|capture| {
+                Some(CaptureKey {
+                    contract: capture.contract.parse().ok()?,
+                    kind: capture.kind.as_deref().and_then(DeployKind::from_name),
+                })
+            })
+            .collect();
+        let tracker = Arc::new(Self {
+            logger,
+            contracts,
+            searchers: searchers.into_iter().collect(),
+            captured: Mutex::new(captured),
+            store,
+        });
+        info!(logger = %telemetry::checksum(&logger), "Tracking capture logger");
+
+        let head = client.get_block_number().await?.as_u64();
+        if let Some(from_block) = from_block {
+            tracker.backfill(&client, from_block, head).await?;
+        }
+
+        let follower = tracker.clone();
+        tokio::spawn(async move {
+            let filter = Filter::new().address(follower.logger);
+            let mut logs = match client.subscribe_logs(&filter).await {
+                Ok(logs) => logs,
+                Err(e) => {
+                    warn!("Failed to subscribe to capture logger {:?}", e);
+                    return;
+                }
+            };
+            //catch up on whatever landed between the backfill and subscribing
+            let caught_up = async {
+                let now = client.get_block_number().await?.as_u64();
+                follower.backfill(&client, head + 1, now).await
+            };
+            if let Err(e) = caught_up.await {
+                warn!("Failed to catch up on capture logger logs {:?}", e);
+            }
+            while let Some(log) = logs.next().await {
+                follower.observe(&client, log).await;
+            }
+            warn!("Capture logger subscription ended");
+        });
+        Ok(tracker)
+    }
+
+    async fn backfill(&self, client: &Provider<Ws>, from: u64, to: u64) -> Result<()> {
+        let filter = Filter::new().address(self.logger);
+        let mut start = from;
+        while start <= to {
+            let end = (start + BACKFILL_CHUNK - 1).min(to);
+            let chunk = filter.clone().from_block(start).to_block(end);
+            for log in metrics::time_rpc("get_logs", client.get_logs(&chunk)).await? {
+                self.observe(client, log).await;
+            }
+            start = end + 1;
+        }
+        Ok(())
+    }
+
+    pub fn is_captured(&self, key: &CaptureKey) -> bool {
+        self.captured.lock().unwrap().contains(key)
+    }
+
+    /// Whether `logs`, e.g. of a simulated claim, register a capture for `contract`.
+    /// Logs naming a different challenge do not count.
+    pub fn registers(&self, logs: &[Log], contract: &Address) -> bool {
+        logs.iter()
+            .filter(|log| log.address == self.logger)
+            .any(|log| {
+                let named: Vec<Address> = address_words(log)
+                    .filter(|word| self.contracts.contains_key(word))
+                    .collect();
+                named.is_empty() || named.contains(contract)
+            })
+    }
+
+    async fn observe(&self, client: &Provider<Ws>, log: Log) {
+        match self.capture(client, &log).await {
+            Ok(Some(capture)) => {
+                if let Err(e) = self.store.record_capture(&capture) {
+                    warn!("Failed to record capture {:?}", e);
+                }
+                let new = self.captured.lock().unwrap().insert(capture.key());
+                if new {
+                    metrics::FLAGS_CAPTURED
+                        .with_label_values(&[capture.flag.name()])
+                        .inc();
+                    info!(
+                        challenge = capture.flag.name(),
+                        contract = %telemetry::checksum(&capture.contract),
+                        kind = capture.kind.map(|kind| kind.name()),
+                        searcher = %telemetry::checksum(&capture.searcher),
+                        tx = %telemetry::hex(&capture.tx_hash),
+                        "Flag captured"
+                    );
+                }
+            }
+            Ok(None) => (),
+            Err(e) => {
+                warn!(logger = %telemetry::checksum(&self.logger), "Failed to read capture log {:?}", e)
+            }
+        }
+    }
+
+    /// The logger's event layout is not in our ABIs, so read the challenge and searcher from
+    /// address-shaped words of the log and fall back to the tx that emitted it.
+    async fn capture(&self, client: &Provider<Ws>, log: &Log) -> Result<Option<Capture>> {
+        let (Some(tx_hash), Some(block)) = (log.transaction_hash, log.block_number) else {
+            return Ok(None);
+        };
+        let words: Vec<Address> = address_words(log).collect();
+        let mut contract = words
+            .iter()
+            .find(|word| self.contracts.contains_key(word))
+            .copied();
+        let others: Vec<&Address> = words
+            .iter()
+            .filter(|word| !self.contracts.contains_key(word))
+            .collect();
+        let mut searcher = others
+            .iter()
+            .find(|word| self.searchers.contains(word))
+            .map(|word| **word);
+        //someone else's capture, no need to look at the tx
+        if searcher.is_none() && !others.is_empty() {
+            return Ok(None);
+        }
+        //a NewContracts claim goes to the child, the tx says which one
+        let new_contracts = contract.is_some_and(|c| self.contracts[&c] == Flag::NewContracts);
+        let mut called = None;
+        if searcher.is_none() || contract.is_none() || new_contracts {
+            let tx = metrics::time_rpc("get_transaction", client.get_transaction(tx_hash))
+                .await?
+                .ok_or_else(|| eyre!("capture tx {:?} not found", tx_hash))?;
+            searcher = searcher.or(Some(tx.from).filter(|from| self.searchers.contains(from)));
+            contract = contract.or(tx.to.filter(|to| self.contracts.contains_key(to)));
+            called = tx.to;
+        }
+        let Some(searcher) = searcher else {
+            return Ok(None);
+        };
+        let Some(contract) = contract else {
+            warn!(tx = %telemetry::hex(&tx_hash), "Capture by our searcher for an unregistered challenge");
+            return Ok(None);
+        };
+        let flag = self.contracts[&contract];
+        let kind = match called {
+            Some(child) if flag == Flag::NewContracts && child != contract => {
+                Some(DeployKind::of_child(client, contract, child).await?)
+            }
+            _ => None,
+        };
+        Ok(Some(Capture {
+            contract,
+            kind,
+            flag,
+            searcher,
+            block: block.as_u64(),
+            tx_hash,
+            log_index: log.log_index.unwrap_or_default().as_u64(),
+        }))
+    }
+}
+
+/// Indexed and data words of a log that hold an address.
+fn address_words(log: &Log) -> impl Iterator<Item = Address> + '_ {
+    log.topics
+        .iter()
+        .skip(1)
+        .filter_map(|topic| as_address(topic.as_bytes()))
+        .chain(log.data.chunks(32).filter_map(as_address))
+}
+
+/// Left padded 32 byte word holding a non-zero address.
+fn as_address(word: &[u8]) -> Option<Address> {
+    if word.len() != 32 || word[..12].iter().any(|b| *b != 0) || word[12..].iter().all(|b| *b == 0)
+    {
+        return None;
+    }
+    Some(Address::from_slice(&word[12..]))
+}
+
+/// Addresses pushed as constants by the code, which is where immutables end up.
+fn pushed_addresses(code: &[u8]) -> Vec<Address> {
+    let mut addresses = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        //PUSH1..PUSH32 carry their operand inline, skip it so it is not read as opcodes
+        if (0x60..=0x7f).contains(&op) {
+            let len = (op - 0x5f) as usize;
+            let operand = &code[(i + 1).min(code.len())..(i + 1 + len).min(code.len())];
+            match len {
+                20 if operand.len() == 20 => addresses.push(Address::from_slice(operand)),
+                32 => addresses.extend(as_address(operand)),
+                _ => (),
+            }
+            i += len;
+        }
+        i += 1;
+    }
+    addresses
+}
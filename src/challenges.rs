@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use ethers::prelude::*;
+use eyre::{eyre, Result};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::abi::mev_share_ctf_simple::MevShareCtfSimple;
+use crate::abi::mev_share_ctf_triple::MevShareCtfTriple;
+use crate::abi::mev_share_magic_number_v3::MevShareMagicNumberV3;
+use crate::ctf::Flag;
+use crate::metrics;
+use crate::telemetry;
+
+/// `activeBlock` of a challenge contract as of the head it was read at.
+#[derive(Debug, Clone, Copy)]
+pub struct ChallengeState {
+    pub active_block: U64,
+    pub read_at: U64,
+}
+
+impl ChallengeState {
+    /// claimReward only passes in the block the challenge was activated in,
+    /// and a claim resets activeBlock so it stays set only while unclaimed.
+    pub fn claimable_in(&self, block: U64) -> bool {
+        !self.active_block.is_zero() && self.active_block == block
+    }
+}
+
+/// Reads `activeBlock` of every registered challenge on each new head.
+/// The NewContracts deployer has no activeBlock, its children are not registered so it is left out.
+pub struct ChallengeMonitor {
+    contracts: HashMap<Address, Flag>,
+    states: Mutex<HashMap<Address, ChallengeState>>,
+    //head the states were last read at
+    read: watch::Sender<U64>,
+}
+
+impl ChallengeMonitor {
+    pub async fn spawn(
+        contracts: &HashMap<Address, Flag>,
+        head: watch::Receiver<U64>,
+        client: Arc<Provider<Ws>>,
+    ) -> Arc<Self> {
+        let contracts = contracts
+            .iter()
+            .filter(|(_, flag)| !matches!(flag, Flag::NewContracts))
+            .map(|(address, flag)| (*address, *flag))
+            .collect();
+        let (read, _) = watch::channel(U64::zero());
+        let monitor = Arc::new(Self {
+            contracts,
+            states: Mutex::new(HashMap::new()),
+            read,
+        });
+        let at = *head.borrow();
+        monitor.refresh(&client, at).await;
+
+        let poller = monitor.clone();
+        let mut head = head;
+        tokio::spawn(async move {
+            while head.changed().await.is_ok() {
+                let at = *head.borrow();
+                poller.refresh(&client, at).await;
+            }
+        });
+        monitor
+    }
+
+    async fn refresh(&self, client: &Arc<Provider<Ws>>, head: U64) {
+        for (address, flag) in &self.contracts {
+            let active_block = match active_block(client, *address, flag).await {
+                Ok(active_block) => active_block,
+                Err(e) => {
+                    warn!(address = %telemetry::checksum(address), "Failed to read activeBlock {:?}", e);
+                    continue;
+                }
+            };
+            let state = ChallengeState {
+                active_block,
+                read_at: head,
+            };
+            if state.claimable_in(head) {
+                debug!(challenge = flag.name(), address = %telemetry::checksum(address), %head, "Challenge activated and unclaimed");
+            }
+            metrics::CHALLENGE_ACTIVE_BLOCK
+                .with_label_values(&[flag.name(), &telemetry::checksum(address)])
+                .set(active_block.as_u64() as i64);
+            self.states.lock().unwrap().insert(*address, state);
+        }
+        self.read.send_replace(head);
+    }
+
+    pub fn state(&self, address: &Address) -> Option<ChallengeState> {
+        self.states.lock().unwrap().get(address).copied()
+    }
+
+    /// State of `address` once it has been read at `block` or later,
+    /// `None` if the challenge is not monitored.
+    pub async fn settled(&self, address: &Address, block: U64) -> Option<ChallengeState> {
+        if !self.contracts.contains_key(address) {
+            return None;
+        }
+        let mut read = self.read.subscribe();
+        if read.wait_for(|at| *at >= block).await.is_err() {
+            return None;
+        }
+        self.state(address)
+    }
+}
+
+/// Read `activeBlock` through the binding for `flag`'s kind of challenge.
+pub async fn active_block(
+    client: &Arc<Provider<Ws>>,
+    address: Address,
+    flag: &Flag,
+) -> Result<U64> {
+    let client = client.clone();
+    let active_block = match flag {
+        Flag::CTFSimple => {
+            let call = MevShareCtfSimple::new(address, client).active_block();
+            metrics::time_rpc("active_block", call.call()).await?
+        }
+        Flag::CTFTriple => {
+            let call = MevShareCtfTriple::new(address, client).active_block();
+            metrics::time_rpc("active_block", call.call()).await?
+        }
+        Flag::MagicNumberV1 | Flag::MagicNumberV2 | Flag::MagicNumberV3 => {
+            let call = MevShareMagicNumberV3::new(address, client).active_block();
+            metrics::time_rpc("active_block", call.call()).await?
+        }
+        Flag::NewContracts | Flag::Rule(_) | Flag::Script(_) => {
+            return Err(eyre!("{} has no activeBlock", flag.name()))
+        }
+    };
+    Ok(active_block.as_u64().into())
+}
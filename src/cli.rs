@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use ethers::prelude::*;
+use eyre::{eyre, Result};
+
+use crate::config::{Config, LogFormat};
+use crate::ctf::{self, Flag};
+use crate::deploy::DeployKind;
+use crate::preflight::{self, ContractCheck};
+use crate::store::Store;
+use crate::telemetry;
+
+/// Flags override env vars, which override the config file.
+#[derive(Parser)]
+#[command(about = "mev-share ctf bot")]
+pub struct Cli {
+    /// Config file [default: mevshare-ctf.toml if it exists]
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Execution client websocket url
+    #[arg(long, global = true)]
+    pub ethereum_api: Option<String>,
+    /// MEV-Share event stream url
+    #[arg(long, global = true)]
+    pub sse_url: Option<String>,
+    /// Chain the bot signs for, checked against the rpc
+    #[arg(long, global = true)]
+    pub chain_id: Option<u64>,
+    #[arg(long, global = true)]
+    pub store_path: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub metrics_addr: Option<String>,
+    #[arg(long, global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the bot (default)
+    Run,
+    /// List recent bundle attempts from the store
+    Attempts {
+        /// Only this challenge, e.g. CTFSimple or MagicNumberV3
+        #[arg(long)]
+        challenge: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Check every registered challenge address against its ABI
+    Contracts,
+    /// Which registered challenges the capture logger credited us with
+    Captures,
+    /// Inspect the resolved configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Validate the config and check the rpc is on the configured chain
+    Check,
+}
+
+pub async fn config_check(config: &Config) -> Result<()> {
+    println!("{:#?}", config);
+    config.validate()?;
+    let provider = Provider::<Ws>::connect(config.ethereum_api()?).await?;
+    config.check_chain_id(&provider).await?;
+    println!("config ok, rpc is on chain {}", config.chain_id);
+    Ok(())
+}
+
+pub async fn contracts_check(config: &Config) -> Result<()> {
+    let provider = Arc::new(Provider::<Ws>::connect(config.ethereum_api()?).await?);
+    config.check_chain_id(&provider).await?;
+    let checks = preflight::check_contracts(&provider, &ctf::contracts()).await;
+    print_contract_checks(&checks);
+    let misconfigured = checks.iter().filter(|check| !check.healthy()).count();
+    if misconfigured > 0 {
+        return Err(eyre!(
+            "{} challenge contracts are misconfigured",
+            misconfigured
+        ));
+    }
+    Ok(())
+}
+
+fn print_contract_checks(checks: &[ContractCheck]) {
+    println!(
+        "{:<14} {:<42}  {:<7} {:<42}  {:>10}  problems",
+        "challenge", "address", "status", "owner", "active"
+    );
+    for check in checks {
+        println!(
+            "{:<14} {:<42}  {:<7} {:<42}  {:>10}  {}",
+            check.flag.name(),
+            telemetry::checksum(&check.address),
+            if check.healthy() { "ok" } else { "BROKEN" },
+            check
+                .owner
+                .map(|owner| telemetry::checksum(&owner))
+                .unwrap_or_else(|| String::from("-")),
+            check
+                .active_block
+                .map(|block| block.to_string())
+                .unwrap_or_else(|| String::from("-")),
+            check.problems.join("; ")
+        );
+    }
+}
+
+pub fn print_captures(store: &Store) -> Result<()> {
+    let captures = store.captures()?;
+    let mut contracts: Vec<_> = ctf::contracts().into_iter().collect();
+    contracts.sort_by_key(|(address, flag)| (flag.name(), *address));
+    println!(
+        "{:<20} {:<42}  {:<8} {:>10}  {:<42}  tx",
+        "challenge", "address", "captured", "block", "searcher"
+    );
+    for (address, flag) in contracts {
+        //each activation of NewContracts is a flag of its own
+        let kinds = match flag {
+            Flag::NewContracts => vec![Some(DeployKind::Create), Some(DeployKind::Create2)],
+            _ => vec![None],
+        };
+        for kind in kinds {
+            let name = match kind {
+                Some(kind) => format!("{} {}", flag.name(), kind.name()),
+                None => flag.name().to_string(),
+            };
+            let capture = captures.iter().find(|capture| {
+                capture.contract.parse::<Address>().ok() == Some(address)
+                    && capture.kind.as_deref() == kind.map(|kind| kind.name())
+            });
+            match capture {
+                Some(c) => println!(
+                    "{:<20} {:<42}  {:<8} {:>10}  {:<42}  {}",
+                    name,
+                    telemetry::checksum(&address),
+                    "yes",
+                    c.block,
+                    c.searcher,
+                    c.tx_hash
+                ),
+                None => println!(
+                    "{:<20} {:<42}  {:<8}",
+                    name,
+                    telemetry::checksum(&address),
+                    "no"
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn print_attempts(store: &Store, challenge: Option<&str>, limit: usize) -> Result<()> {
+    let attempts = store.recent_attempts(challenge, limit)?;
+    println!(
+        "{:>6}  {:<14} {:>10}  {:>3}  {:<9} {:>10}  event",
+        "id", "challenge", "block", "txs", "outcome", "created"
+    );
+    for a in attempts {
+        println!(
+            "{:>6}  {:<14} {:>10}  {:>3}  {:<9} {:>10}  {}",
+            a.id,
+            a.challenge,
+            a.target_block,
+            a.txs,
+            a.outcome,
+            a.created_at,
+            a.event_hash.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,586 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use dotenvy::var;
+use ethers::prelude::*;
+use ethers::utils::parse_ether;
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+use url::Url;
+
+use crate::bid::{BidEngine, BidStrategy};
+use crate::bundle::{BundleOptions, BundleSettings};
+use crate::cli::Cli;
+use crate::ctf;
+use crate::deploy::InitCode;
+use crate::executor::TripleStrategy;
+use crate::registry::{AbiRegistry, RegistryEntry};
+use crate::rules::{RuleConfig, RuleEngine};
+use crate::scripts;
+use crate::signer::{BotSigner, SignerConfig};
+
+pub const DEFAULT_CONFIG_PATH: &str = "mevshare-ctf.toml";
+
+/// A value that must never end up in logs, `Debug` prints a placeholder.
+#[derive(Clone, Deserialize)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([redacted])")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RelayConfig {
+    pub name: String,
+    pub url: String,
+    /// minimum spacing between bundles sent to this relay
+    #[serde(default = "default_min_interval_ms")]
+    pub min_interval_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+/// One source of settings, every field optional so sources can be stacked.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Layer {
+    /// shorthand for a `private_key` signer
+    flashbot_key: Option<Secret>,
+    bot_key: Option<Secret>,
+    flashbot_signer: Option<SignerConfig>,
+    bot_signer: Option<SignerConfig>,
+    /// searcher wallets used alongside the bot key
+    wallets: Option<Vec<SignerConfig>>,
+    /// in ether, wallets below this are left out of rotation
+    min_wallet_balance: Option<String>,
+    /// in ether, warn once a wallet's uncommitted balance drops below this
+    low_balance_alert: Option<String>,
+    /// challenge name or `default` -> bid strategy
+    bids: Option<HashMap<String, BidStrategy>>,
+    /// contract that forwards msg.value to block.coinbase, needed for coinbase transfer bids
+    coinbase_payer: Option<Address>,
+    /// challenge name or `default` -> privacy/validity fields of every bundle
+    bundle_options: Option<HashMap<String, BundleOptions>>,
+    /// MagicNumberV3 attempts, one per wallet, never spent on a guess simulation did not verify
+    magic_v3_reserve_attempts: Option<usize>,
+    /// times to resend a solve for the next block while the activation it backruns is pending
+    activation_retries: Option<u64>,
+    /// where the challenges register captures, found from the challenges themselves if unset
+    capture_logger: Option<Address>,
+    /// block to backfill capture logger logs from, only new logs are followed if unset
+    captures_from_block: Option<u64>,
+    /// multi_tx or executor
+    triple_strategy: Option<TripleStrategy>,
+    /// deployed `ClaimExecutor`, a new one is deployed if the executor strategy is used without it
+    claim_executor: Option<Address>,
+    ethereum_api: Option<Secret>,
+    sse_url: Option<String>,
+    chain_id: Option<u64>,
+    relays: Option<Vec<RelayConfig>>,
+    max_in_flight: Option<usize>,
+    store_path: Option<PathBuf>,
+    metrics_addr: Option<String>,
+    log_format: Option<LogFormat>,
+    otlp_endpoint: Option<String>,
+    /// deployer address -> `0x` prefixed init code or a path to an artifact
+    init_code: Option<HashMap<Address, String>>,
+    /// where ABIs are loaded from at startup
+    abi_dir: Option<PathBuf>,
+    /// challenge address -> name and ABI, on top of the built in challenges
+    registry: Option<HashMap<Address, RegistryEntry>>,
+    /// hint -> call rules for challenges without a built in solver
+    rules: Option<Vec<RuleConfig>>,
+    /// `*.rhai` solvers, reloaded when they change
+    scripts_dir: Option<PathBuf>,
+}
+
+impl Layer {
+    /// Fields set in `over` win.
+    fn merge(self, over: Layer) -> Layer {
+        Layer {
+            flashbot_key: over.flashbot_key.or(self.flashbot_key),
+            bot_key: over.bot_key.or(self.bot_key),
+            flashbot_signer: over.flashbot_signer.or(self.flashbot_signer),
+            bot_signer: over.bot_signer.or(self.bot_signer),
+            wallets: over.wallets.or(self.wallets),
+            min_wallet_balance: over.min_wallet_balance.or(self.min_wallet_balance),
+            low_balance_alert: over.low_balance_alert.or(self.low_balance_alert),
+            bids: over.bids.or(self.bids),
+            coinbase_payer: over.coinbase_payer.or(self.coinbase_payer),
+            bundle_options: over.bundle_options.or(self.bundle_options),
+            magic_v3_reserve_attempts: over
+                .magic_v3_reserve_attempts
+                .or(self.magic_v3_reserve_attempts),
+            activation_retries: over.activation_retries.or(self.activation_retries),
+            capture_logger: over.capture_logger.or(self.capture_logger),
+            captures_from_block: over.captures_from_block.or(self.captures_from_block),
+            triple_strategy: over.triple_strategy.or(self.triple_strategy),
+            claim_executor: over.claim_executor.or(self.claim_executor),
+            ethereum_api: over.ethereum_api.or(self.ethereum_api),
+            sse_url: over.sse_url.or(self.sse_url),
+            chain_id: over.chain_id.or(self.chain_id),
+            relays: over.relays.or(self.relays),
+            max_in_flight: over.max_in_flight.or(self.max_in_flight),
+            store_path: over.store_path.or(self.store_path),
+            metrics_addr: over.metrics_addr.or(self.metrics_addr),
+            log_format: over.log_format.or(self.log_format),
+            otlp_endpoint: over.otlp_endpoint.or(self.otlp_endpoint),
+            init_code: over.init_code.or(self.init_code),
+            abi_dir: over.abi_dir.or(self.abi_dir),
+            registry: over.registry.or(self.registry),
+            rules: over.rules.or(self.rules),
+            scripts_dir: over.scripts_dir.or(self.scripts_dir),
+        }
+    }
+
+    fn from_file(path: &Path) -> Result<Layer> {
+        let contents = fs::read_to_string(path).wrap_err_with(|| format!("reading {:?}", path))?;
+        let layer: Layer =
+            toml::from_str(&contents).wrap_err_with(|| format!("parsing {:?}", path))?;
+        Ok(layer.normalize())
+    }
+
+    /// Fold the raw key shorthands into signer configs so later layers override either form.
+    fn normalize(mut self) -> Layer {
+        if let Some(key) = self.flashbot_key.take() {
+            self.flashbot_signer = Some(SignerConfig::PrivateKey { key });
+        }
+        if let Some(key) = self.bot_key.take() {
+            self.bot_signer = Some(SignerConfig::PrivateKey { key });
+        }
+        self
+    }
+
+    /// Env names are the ones the bot has always read from `.env`.
+    fn from_env() -> Result<Layer> {
+        let layer = Layer {
+            flashbot_key: var("FlashbotKey").ok().map(Secret),
+            bot_key: var("BotKey").ok().map(Secret),
+            flashbot_signer: signer_from_env("Flashbot")?,
+            bot_signer: signer_from_env("Bot")?,
+            wallets: None,
+            min_wallet_balance: var("MinWalletBalance").ok(),
+            low_balance_alert: var("LowBalanceAlert").ok(),
+            bids: None,
+            coinbase_payer: parse_env("CoinbasePayer")?,
+            bundle_options: None,
+            magic_v3_reserve_attempts: parse_env("MagicV3ReserveAttempts")?,
+            activation_retries: parse_env("ActivationRetries")?,
+            capture_logger: parse_env("CaptureLogger")?,
+            captures_from_block: parse_env("CapturesFromBlock")?,
+            triple_strategy: match var("TripleStrategy").ok() {
+                Some(strategy) => Some(
+                    <TripleStrategy as clap::ValueEnum>::from_str(&strategy, true)
+                        .map_err(|_| eyre!("TripleStrategy must be multi_tx or executor"))?,
+                ),
+                None => None,
+            },
+            claim_executor: parse_env("ClaimExecutor")?,
+            ethereum_api: var("EthereumApi").ok().map(Secret),
+            sse_url: var("SseUrl").ok(),
+            chain_id: parse_env("ChainId")?,
+            relays: None,
+            max_in_flight: parse_env("MaxInFlight")?,
+            store_path: var("StorePath").ok().map(PathBuf::from),
+            metrics_addr: var("MetricsAddr").ok(),
+            log_format: match var("LogFormat").ok() {
+                Some(format) => Some(
+                    <LogFormat as clap::ValueEnum>::from_str(&format, true)
+                        .map_err(|_| eyre!("LogFormat must be pretty or json"))?,
+                ),
+                None => None,
+            },
+            otlp_endpoint: var("OtlpEndpoint").ok(),
+            init_code: None,
+            abi_dir: var("AbiDir").ok().map(PathBuf::from),
+            registry: None,
+            rules: None,
+            scripts_dir: var("ScriptsDir").ok().map(PathBuf::from),
+        };
+        Ok(layer.normalize())
+    }
+
+    fn from_cli(cli: &Cli) -> Layer {
+        Layer {
+            ethereum_api: cli.ethereum_api.clone().map(Secret),
+            sse_url: cli.sse_url.clone(),
+            chain_id: cli.chain_id,
+            store_path: cli.store_path.clone(),
+            metrics_addr: cli.metrics_addr.clone(),
+            log_format: cli.log_format,
+            ..Default::default()
+        }
+    }
+}
+
+/// Bot settings, resolved from defaults < config file < env < cli flags.
+/// Keys are only taken from the file or env so they never show up in process listings.
+#[derive(Debug)]
+pub struct Config {
+    pub flashbot_signer: Option<SignerConfig>,
+    pub bot_signer: Option<SignerConfig>,
+    pub wallets: Vec<SignerConfig>,
+    pub min_wallet_balance: String,
+    pub low_balance_alert: String,
+    pub bids: HashMap<String, BidStrategy>,
+    pub coinbase_payer: Option<Address>,
+    pub bundle_options: HashMap<String, BundleOptions>,
+    pub magic_v3_reserve_attempts: usize,
+    pub activation_retries: u64,
+    pub capture_logger: Option<Address>,
+    pub captures_from_block: Option<u64>,
+    pub triple_strategy: TripleStrategy,
+    pub claim_executor: Option<Address>,
+    /// rpc urls tend to carry an api key so this is redacted too
+    pub ethereum_api: Option<Secret>,
+    pub sse_url: String,
+    pub chain_id: u64,
+    pub relays: Vec<RelayConfig>,
+    pub max_in_flight: usize,
+    pub store_path: PathBuf,
+    pub metrics_addr: String,
+    pub log_format: LogFormat,
+    pub otlp_endpoint: Option<String>,
+    pub init_code: HashMap<Address, String>,
+    pub abi_dir: Option<PathBuf>,
+    pub registry: HashMap<Address, RegistryEntry>,
+    pub rules: Vec<RuleConfig>,
+    pub scripts_dir: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn load(cli: &Cli) -> Result<Self> {
+        //an explicitly named file has to exist, the default one is optional
+        let file = match cli
+            .config
+            .clone()
+            .or_else(|| var("ConfigPath").ok().map(PathBuf::from))
+        {
+            Some(path) => Layer::from_file(&path)?,
+            None => {
+                let path = PathBuf::from(DEFAULT_CONFIG_PATH);
+                if path.exists() {
+                    Layer::from_file(&path)?
+                } else {
+                    Layer::default()
+                }
+            }
+        };
+        let layer = file.merge(Layer::from_env()?).merge(Layer::from_cli(cli));
+
+        Ok(Config {
+            flashbot_signer: layer.flashbot_signer,
+            bot_signer: layer.bot_signer,
+            wallets: layer.wallets.unwrap_or_default(),
+            min_wallet_balance: layer
+                .min_wallet_balance
+                .unwrap_or_else(|| String::from("0.01")),
+            low_balance_alert: layer
+                .low_balance_alert
+                .unwrap_or_else(|| String::from("0.05")),
+            bids: layer.bids.unwrap_or_default(),
+            coinbase_payer: layer.coinbase_payer,
+            bundle_options: layer.bundle_options.unwrap_or_default(),
+            magic_v3_reserve_attempts: layer.magic_v3_reserve_attempts.unwrap_or(1),
+            activation_retries: layer.activation_retries.unwrap_or(2),
+            capture_logger: layer.capture_logger,
+            captures_from_block: layer.captures_from_block,
+            triple_strategy: layer.triple_strategy.unwrap_or_default(),
+            claim_executor: layer.claim_executor,
+            ethereum_api: layer.ethereum_api,
+            sse_url: layer
+                .sse_url
+                .unwrap_or_else(|| String::from("https://mev-share-goerli.flashbots.net")),
+            chain_id: layer.chain_id.unwrap_or(5),
+            relays: layer.relays.unwrap_or_else(|| {
+                vec![RelayConfig {
+                    name: String::from("flashbots-goerli"),
+                    url: String::from("https://relay-goerli.flashbots.net:443"),
+                    min_interval_ms: default_min_interval_ms(),
+                }]
+            }),
+            max_in_flight: layer.max_in_flight.unwrap_or(8),
+            store_path: layer
+                .store_path
+                .unwrap_or_else(|| PathBuf::from("mevshare-ctf.db")),
+            metrics_addr: layer
+                .metrics_addr
+                .unwrap_or_else(|| String::from("127.0.0.1:9898")),
+            log_format: layer.log_format.unwrap_or_default(),
+            otlp_endpoint: layer.otlp_endpoint,
+            init_code: layer.init_code.unwrap_or_default(),
+            abi_dir: layer.abi_dir,
+            registry: layer.registry.unwrap_or_default(),
+            rules: layer.rules.unwrap_or_default(),
+            scripts_dir: layer.scripts_dir,
+        })
+    }
+
+    /// Check everything that can be checked offline, reporting every problem at once.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        for (name, signer) in [
+            ("FlashbotKey", &self.flashbot_signer),
+            ("BotKey", &self.bot_signer),
+        ] {
+            match signer {
+                None => problems.push(format!("{} is not set", name)),
+                Some(signer) => problems.extend(signer.problems(name)),
+            }
+        }
+        for (i, wallet) in self.wallets.iter().enumerate() {
+            problems.extend(wallet.problems(&format!("wallets[{}]", i)));
+        }
+        for (name, amount) in [
+            ("MinWalletBalance", &self.min_wallet_balance),
+            ("LowBalanceAlert", &self.low_balance_alert),
+        ] {
+            if parse_ether(amount).is_err() {
+                problems.push(format!("{} must be an ether amount", name));
+            }
+        }
+        //rules and scripts get their own bids and bundle options by name
+        let mut named: Vec<String> = self.rules.iter().map(|rule| rule.name.clone()).collect();
+        if let Some(dir) = &self.scripts_dir {
+            if dir.is_dir() {
+                named.extend(scripts::script_names(dir));
+            } else {
+                problems.push(format!("ScriptsDir {:?} is not a directory", dir));
+            }
+        }
+        check_challenges(&mut problems, "bids", self.bids.keys(), &named);
+        if let Err(e) = self.bid_engine() {
+            problems.push(e.to_string());
+        }
+        check_challenges(
+            &mut problems,
+            "bundle_options",
+            self.bundle_options.keys(),
+            &named,
+        );
+        for (challenge, options) in &self.bundle_options {
+            problems.extend(options.problems(&format!("bundle_options.{}", challenge)));
+        }
+        match &self.ethereum_api {
+            None => problems.push(String::from("EthereumApi is not set")),
+            Some(url) => check_url(&mut problems, "EthereumApi", url.expose(), &["ws", "wss"]),
+        }
+        check_url(&mut problems, "SseUrl", &self.sse_url, &["http", "https"]);
+        if self.relays.is_empty() {
+            problems.push(String::from("no relays configured"));
+        }
+        for relay in &self.relays {
+            check_url(&mut problems, &relay.name, &relay.url, &["http", "https"]);
+        }
+        if self.max_in_flight == 0 {
+            problems.push(String::from("MaxInFlight must be at least 1"));
+        }
+        if self.metrics_addr.parse::<SocketAddr>().is_err() {
+            problems.push(format!(
+                "MetricsAddr {:?} is not a socket address",
+                self.metrics_addr
+            ));
+        }
+        match self.abi_registry() {
+            Ok(abis) => {
+                if let Err(e) = self.rule_engine(&abis) {
+                    problems.push(format!("{:#}", e));
+                }
+            }
+            Err(e) => problems.push(format!("{:#}", e)),
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre!("invalid config:\n  - {}", problems.join("\n  - ")))
+        }
+    }
+
+    /// The rpc has to be on the chain we sign transactions for.
+    pub async fn check_chain_id<M: Middleware>(&self, client: &M) -> Result<()> {
+        let chain_id = client
+            .get_chainid()
+            .await
+            .map_err(|e| eyre!("Failed to fetch chain id {:?}", e))?;
+        if chain_id != U256::from(self.chain_id) {
+            return Err(eyre!(
+                "EthereumApi is on chain {} but ChainId is {}",
+                chain_id,
+                self.chain_id
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn flashbot_signer(&self) -> Result<BotSigner> {
+        build_signer("FlashbotKey", &self.flashbot_signer, self.chain_id).await
+    }
+
+    pub async fn tx_signer(&self) -> Result<BotSigner> {
+        build_signer("BotKey", &self.bot_signer, self.chain_id).await
+    }
+
+    /// The bot key followed by any extra wallets, all signing for `chain_id`.
+    pub async fn wallet_signers(&self) -> Result<Vec<BotSigner>> {
+        let mut signers = vec![self.tx_signer().await?];
+        for (i, wallet) in self.wallets.iter().enumerate() {
+            signers.push(
+                wallet
+                    .build(self.chain_id)
+                    .await
+                    .wrap_err_with(|| format!("wallets[{}] signer", i))?,
+            );
+        }
+        Ok(signers)
+    }
+
+    pub fn min_wallet_balance(&self) -> Result<U256> {
+        parse_ether(&self.min_wallet_balance)
+            .map_err(|_| eyre!("MinWalletBalance must be an ether amount"))
+    }
+
+    pub fn low_balance_alert(&self) -> Result<U256> {
+        parse_ether(&self.low_balance_alert)
+            .map_err(|_| eyre!("LowBalanceAlert must be an ether amount"))
+    }
+
+    pub fn bid_engine(&self) -> Result<BidEngine> {
+        BidEngine::new(&self.bids, self.coinbase_payer)
+    }
+
+    pub fn bundle_settings(&self) -> BundleSettings {
+        BundleSettings::new(self.bundle_options.clone())
+    }
+
+    pub fn ethereum_api(&self) -> Result<&str> {
+        self.ethereum_api
+            .as_ref()
+            .map(Secret::expose)
+            .ok_or_else(|| eyre!("EthereumApi is not set"))
+    }
+
+    /// ABIs from `abi_dir` with the built in challenges and `registry` registered against them.
+    pub fn abi_registry(&self) -> Result<AbiRegistry> {
+        AbiRegistry::load(self.abi_dir.as_deref(), &self.registry)
+    }
+
+    pub fn rule_engine(&self, abis: &AbiRegistry) -> Result<RuleEngine> {
+        RuleEngine::new(&self.rules, abis)
+    }
+
+    /// Init code overrides from config, `0x` prefixed values are hex and anything else a path.
+    pub fn init_code_overrides(&self) -> HashMap<Address, InitCode> {
+        self.init_code
+            .iter()
+            .map(|(deployer, value)| {
+                let source = if value.starts_with("0x") {
+                    InitCode::Hex(value.clone())
+                } else {
+                    InitCode::File(PathBuf::from(value))
+                };
+                (*deployer, source)
+            })
+            .collect()
+    }
+}
+
+fn default_min_interval_ms() -> u64 {
+    100
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    match var(name) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| eyre!("{} has an invalid value", name)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// `<prefix>KeyFile`, `<prefix>Keystore` or `<prefix>RemoteSigner`, the raw key is handled by `normalize`.
+fn signer_from_env(prefix: &str) -> Result<Option<SignerConfig>> {
+    if let Ok(path) = var(format!("{}KeyFile", prefix)) {
+        return Ok(Some(SignerConfig::KeyFile { path: path.into() }));
+    }
+    if let Ok(path) = var(format!("{}Keystore", prefix)) {
+        return Ok(Some(SignerConfig::Keystore {
+            path: path.into(),
+            passphrase: var(format!("{}KeystorePassphrase", prefix))
+                .ok()
+                .map(Secret),
+            passphrase_file: var(format!("{}KeystorePassphraseFile", prefix))
+                .ok()
+                .map(PathBuf::from),
+        }));
+    }
+    if let Ok(url) = var(format!("{}RemoteSigner", prefix)) {
+        let name = format!("{}Address", prefix);
+        let address = parse_env(&name)?.ok_or_else(|| eyre!("{} is not set", name))?;
+        return Ok(Some(SignerConfig::Remote { url, address }));
+    }
+    Ok(None)
+}
+
+async fn build_signer(
+    name: &str,
+    signer: &Option<SignerConfig>,
+    chain_id: u64,
+) -> Result<BotSigner> {
+    signer
+        .as_ref()
+        .ok_or_else(|| eyre!("{} is not set", name))?
+        .build(chain_id)
+        .await
+        .wrap_err_with(|| format!("{} signer", name))
+}
+
+/// Per challenge tables are keyed by `Flag::name`, a rule or script name, or `default`.
+fn check_challenges<'a>(
+    problems: &mut Vec<String>,
+    table: &str,
+    keys: impl Iterator<Item = &'a String>,
+    named: &[String],
+) {
+    let challenges: Vec<&str> = ctf::contracts()
+        .values()
+        .map(|flag| flag.name())
+        .chain(named.iter().map(String::as_str))
+        .collect();
+    for key in keys {
+        if key != "default" && !challenges.contains(&key.as_str()) {
+            problems.push(format!("{}.{} is not a challenge", table, key));
+        }
+    }
+}
+
+fn check_url(problems: &mut Vec<String>, name: &str, url: &str, schemes: &[&str]) {
+    match Url::parse(url) {
+        Ok(parsed) if schemes.contains(&parsed.scheme()) => (),
+        Ok(parsed) => problems.push(format!(
+            "{} must use {} not {}",
+            name,
+            schemes.join("/"),
+            parsed.scheme()
+        )),
+        //urls carry api keys often enough that they are not echoed either
+        Err(_) => problems.push(format!("{} is not a valid url", name)),
+    }
+}
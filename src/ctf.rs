@@ -1,16 +1,85 @@
 use ethers::abi::Address;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
-#[derive(Debug)]
+use once_cell::sync::Lazy;
+
+use crate::abi::Contract;
+use crate::deploy::InitCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Flag {
-    CTFSimple(bool),
-    MagicNumberV1(bool),
-    MagicNumberV2(bool),
-    MagicNumberV3(bool),
-    NewContracts(bool),
-    CTFTriple(bool),
-} //bool is whether I have completed -> bit janky
+    CTFSimple,
+    MagicNumberV1,
+    MagicNumberV2,
+    MagicNumberV3,
+    NewContracts,
+    CTFTriple,
+    /// a `[[rules]]` entry from config, by name
+    Rule(&'static str),
+    /// a script in the scripts dir, by file stem
+    Script(&'static str),
+}
+
+impl Flag {
+    /// Submission queue priority, higher goes first.
+    /// Magic number spams a bundle per candidate so it goes last to not starve the rest.
+    pub fn priority(&self) -> u8 {
+        match self {
+            Flag::CTFTriple | Flag::NewContracts => 3,
+            Flag::CTFSimple => 2,
+            Flag::MagicNumberV1 | Flag::MagicNumberV2 | Flag::MagicNumberV3 => 1,
+            Flag::Rule(_) | Flag::Script(_) => 2,
+        }
+    }
+
+    /// Challenge name, used as a key in the store and config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flag::CTFSimple => "CTFSimple",
+            Flag::MagicNumberV1 => "MagicNumberV1",
+            Flag::MagicNumberV2 => "MagicNumberV2",
+            Flag::MagicNumberV3 => "MagicNumberV3",
+            Flag::NewContracts => "NewContracts",
+            Flag::CTFTriple => "CTFTriple",
+            Flag::Rule(name) | Flag::Script(name) => name,
+        }
+    }
+
+    /// Contract in `abi/` the challenge speaks, rules and scripts name their ABI themselves.
+    pub fn contract(&self) -> Option<Contract> {
+        match self {
+            Flag::CTFSimple => Some(Contract::MevShareCtfSimple),
+            Flag::MagicNumberV1 | Flag::MagicNumberV2 | Flag::MagicNumberV3 => {
+                Some(Contract::MevShareMagicNumberV3)
+            }
+            Flag::NewContracts => Some(Contract::MevShareNewContracts),
+            Flag::CTFTriple => Some(Contract::MevShareCtfTriple),
+            Flag::Rule(_) | Flag::Script(_) => None,
+        }
+    }
+}
+
+/// Names from config as `&'static str` so they fit in a `Flag`, each leaked once.
+pub fn intern(name: &str) -> &'static str {
+    static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+    let mut names = NAMES.lock().unwrap();
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(interned);
+    interned
+}
+
+/// The CTFSimple instance whose hints hide both `to` and logs, so any hint naming no single
+/// contract is taken to be it.
+pub fn hidden_hint_contract() -> Address {
+    "0x20a1A5857fDff817aa1BD8097027a841D4969AA5"
+        .parse::<Address>()
+        .unwrap()
+}
 
 pub fn contracts() -> HashMap<Address, Flag> {
     HashMap::from([
@@ -18,55 +87,61 @@ pub fn contracts() -> HashMap<Address, Flag> {
             "0x65459dd36b03af9635c06bad1930db660b968278"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::CTFSimple(true),
+            Flag::CTFSimple,
         ),
         (
             "0x98997b55bb271e254bec8b85763480719dab0e53"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::CTFSimple(true),
+            Flag::CTFSimple,
         ),
         (
             "0x1cddb0ba9265bb3098982238637c2872b7d12474"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::CTFSimple(true),
+            Flag::CTFSimple,
         ),
         (
             "0x118bcb654d9a7006437895b51b5cd4946bf6cdc2"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::MagicNumberV1(true),
+            Flag::MagicNumberV1,
         ),
         (
             "0x9be957d1c1c1f86ba9a2e1215e9d9eefde615a56"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::MagicNumberV2(true),
+            Flag::MagicNumberV2,
         ),
         (
             "0xe8b7475e2790409715af793f799f3cc80de6f071"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::MagicNumberV3(true),
+            Flag::MagicNumberV3,
         ),
         (
             "0x5eA0feA0164E5AA58f407dEBb344876b5ee10DEA"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::NewContracts(true),
+            Flag::NewContracts,
         ),
         (
             "0x1ea6fb65bab1f405f8bdb26d163e6984b9108478"
                 .parse::<Address>()
                 .unwrap(),
-            Flag::CTFTriple(true),
-        ),
-        (
-            "0x20a1A5857fDff817aa1BD8097027a841D4969AA5"
-                .parse::<Address>()
-                .unwrap(),
-            Flag::CTFSimple(true),
+            Flag::CTFTriple,
         ),
+        (hidden_hint_contract(), Flag::CTFSimple),
     ])
 }
+
+/// deployer address -> init code of the contract it deploys, compiled in so the bot
+/// does not depend on where it is started from
+pub fn init_code() -> HashMap<Address, InitCode> {
+    HashMap::from([(
+        "0x5eA0feA0164E5AA58f407dEBb344876b5ee10DEA"
+            .parse::<Address>()
+            .unwrap(),
+        InitCode::Hex(include_str!("../abi/mev_share_new_contract.bin").to_string()),
+    )])
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethers::prelude::*;
+use ethers::utils::{get_contract_address, get_create2_address};
+use eyre::{eyre, Result};
+
+use crate::abi::mev_share_new_contract::MevShareNewContract;
+use crate::abi::mev_share_new_contracts::{ActivateFilter, MevShareNewContractsEvents};
+use crate::metrics;
+
+/// How many nonces past the deployer's current one to try when the created address is hidden.
+pub const CREATE_NONCE_WINDOW: u64 = 3;
+
+/// Where a deployer's child init code comes from.
+pub enum InitCode {
+    /// hex file (`.bin`) or a foundry/hardhat artifact (`.json` with a `bytecode` field)
+    File(PathBuf),
+    /// hex string straight from config
+    Hex(String),
+}
+
+/// How a challenge deployed (or is about to deploy) its child contract.
+#[derive(Debug, Clone, Copy)]
+pub enum Deployment {
+    /// CREATE2 from the deployer with this salt
+    Salt(H256),
+    /// address was given to us directly
+    Known(Address),
+    /// CREATE at a nonce we can't see, try this many from the deployer's current nonce
+    NextNonces(u64),
+}
+
+impl Deployment {
+    pub fn kind(&self) -> DeployKind {
+        match self {
+            Deployment::Salt(_) => DeployKind::Create2,
+            Deployment::Known(_) | Deployment::NextNonces(_) => DeployKind::Create,
+        }
+    }
+}
+
+/// Which of the two `MevShareNewContracts` activations deployed a child, each is a flag of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeployKind {
+    /// `activateRewardNewContract`, CREATE announced by `Activate`
+    Create,
+    /// `activateRewardBySalt`, CREATE2 announced by `ActivateBySalt`
+    Create2,
+}
+
+impl DeployKind {
+    /// Name in the store.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeployKind::Create => "create",
+            DeployKind::Create2 => "create2",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "create" => Some(DeployKind::Create),
+            "create2" => Some(DeployKind::Create2),
+            _ => None,
+        }
+    }
+
+    /// How `parent` deployed `child`. Every deploy takes a nonce, so a CREATE child sits at
+    /// one of the nonces the parent has used and anything else came from CREATE2.
+    pub async fn of_child<M: Middleware>(
+        client: &M,
+        parent: Address,
+        child: Address,
+    ) -> Result<Self>
+    where
+        M::Error: 'static,
+    {
+        let nonce = metrics::time_rpc(
+            "get_transaction_count",
+            client.get_transaction_count(parent, None),
+        )
+        .await?;
+        //contract nonces start at 1
+        let created = (1..nonce.as_u64()).any(|n| get_contract_address(parent, n) == child);
+        Ok(if created {
+            DeployKind::Create
+        } else {
+            DeployKind::Create2
+        })
+    }
+}
+
+/// Resolves addresses of contracts deployed by challenge contracts.
+pub struct DeployResolver {
+    init_code: HashMap<Address, Bytes>,
+}
+
+impl DeployResolver {
+    /// Load the child init code for each deployer up front so the hot path never touches disk.
+    pub fn load(sources: HashMap<Address, InitCode>) -> Result<Self> {
+        let mut init_code = HashMap::new();
+        for (deployer, source) in sources {
+            let code = match source {
+                InitCode::File(path) => load_init_code_file(&path)?,
+                InitCode::Hex(hex) => decode_hex(&hex)?,
+            };
+            init_code.insert(deployer, code);
+        }
+        Ok(Self { init_code })
+    }
+
+    pub fn resolve(&self, deployer: Address, deployment: Deployment) -> Result<Address> {
+        match deployment {
+            Deployment::Salt(salt) => {
+                let init_code = self
+                    .init_code
+                    .get(&deployer)
+                    .ok_or_else(|| eyre!("No init code registered for deployer {:?}", deployer))?;
+                Ok(get_create2_address(deployer, salt, init_code.clone()))
+            }
+            Deployment::Known(address) => Ok(address),
+            Deployment::NextNonces(_) => Err(eyre!("Nonce unknown, use candidates")),
+        }
+    }
+
+    /// Every address the deployment could end up at, one unless the deployer nonce has to be guessed.
+    pub async fn candidates<M: Middleware + 'static>(
+        &self,
+        client: &Arc<M>,
+        deployer: Address,
+        deployment: Deployment,
+    ) -> Result<Vec<Address>> {
+        match deployment {
+            Deployment::NextNonces(count) => {
+                //contract nonces start at 1 and only move on CREATE so this is the next deploy
+                let nonce = client
+                    .get_transaction_count(deployer, None)
+                    .await
+                    .map_err(|e| eyre!("{:?}", e))?;
+                Ok((0..count)
+                    .map(|i| get_contract_address(deployer, nonce + i))
+                    .collect())
+            }
+            deployment => Ok(vec![self.resolve(deployer, deployment)?]),
+        }
+    }
+
+    /// Poll until `address` has code, false if it never shows up within `blocks` blocks.
+    pub async fn verify_deployed<M: Middleware + 'static>(
+        client: Arc<M>,
+        address: Address,
+        blocks: u64,
+    ) -> Result<bool> {
+        for _ in 0..blocks {
+            let code = client
+                .get_code(address, None)
+                .await
+                .map_err(|e| eyre!("{:?}", e))?;
+            if !code.is_empty() {
+                return Ok(true);
+            }
+            tokio::time::sleep(Duration::from_secs(12)).await;
+        }
+        Ok(false)
+    }
+}
+
+/// A `MevShareNewContracts` parent and a child it deployed. Claims go to the child,
+/// which calls `proxyRegisterCapture` on the parent, so the capture is registered as the parent's.
+#[derive(Debug, Clone, Copy)]
+pub struct NewContractPair {
+    pub parent: Address,
+    pub child: Address,
+}
+
+impl NewContractPair {
+    /// The parent has no activeBlock, the child is activated as it is deployed.
+    pub async fn child_active_block(&self, client: &Arc<Provider<Ws>>) -> Result<U64> {
+        let call = MevShareNewContract::new(self.child, client.clone()).active_block();
+        let active_block = metrics::time_rpc("active_block", call.call()).await?;
+        Ok(active_block.as_u64().into())
+    }
+}
+
+/// Pull the deployment out of a `MevShareNewContracts` log.
+/// Falls back to guessing nonces when the hint hides the log or its data.
+pub fn new_contracts_deployment(log: Option<Log>) -> Result<Deployment> {
+    let Some(log) = log else {
+        return Ok(Deployment::NextNonces(CREATE_NONCE_WINDOW));
+    };
+    if log.topics.first() == Some(&ActivateFilter::signature()) && log.data.is_empty() {
+        return Ok(Deployment::NextNonces(CREATE_NONCE_WINDOW));
+    }
+    let event: MevShareNewContractsEvents = ethers::contract::parse_log(log)?;
+    match event {
+        MevShareNewContractsEvents::ActivateFilter(e) => {
+            Ok(Deployment::Known(e.newly_deployed_contract))
+        }
+        MevShareNewContractsEvents::ActivateBySaltFilter(e) => Ok(Deployment::Salt(e.salt.into())),
+        e => Err(eyre!("Not a deployment event {:?}", e)),
+    }
+}
+
+/// The activation a `MevShareNewContracts` hint is for, read from the same log the solver uses.
+pub fn hinted_kind(event: &mev_share::sse::Event) -> Option<DeployKind> {
+    let log = event.logs.last().map(|log| Log {
+        address: log.address,
+        topics: log.topics.clone(),
+        data: log.data.clone(),
+        ..Default::default()
+    });
+    new_contracts_deployment(log)
+        .ok()
+        .map(|deployment| deployment.kind())
+}
+
+fn load_init_code_file(path: &Path) -> Result<Bytes> {
+    let contents = fs::read_to_string(path)?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        //forge puts it under bytecode.object, hardhat straight under bytecode
+        let artifact: serde_json::Value = serde_json::from_str(&contents)?;
+        let bytecode = artifact
+            .get("bytecode")
+            .and_then(|b| b.get("object").unwrap_or(b).as_str())
+            .ok_or_else(|| eyre!("No bytecode in artifact {:?}", path))?;
+        decode_hex(bytecode)
+    } else {
+        decode_hex(&contents)
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Bytes> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    Ok(ethers::utils::hex::decode(hex)?.into())
+}
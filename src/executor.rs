@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use ethers::abi::AbiEncode;
+use ethers::prelude::*;
+use eyre::{eyre, Result};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::abi::claim_executor::{
+    ClaimExecutor, ClaimRewardsCall, CLAIMEXECUTOR_DEPLOYED_BYTECODE,
+};
+use crate::metrics;
+use crate::signer::BotSigner;
+use crate::telemetry;
+
+/// How the triple challenge lands its claims.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum TripleStrategy {
+    /// one tx per claim on consecutive nonces
+    #[default]
+    MultiTx,
+    /// every claim in a single tx through our `ClaimExecutor`
+    Executor,
+}
+
+/// The configured executor once its code checks out, otherwise a fresh one deployed by `signer`.
+pub async fn claim_executor(
+    client: &Arc<Provider<Ws>>,
+    configured: Option<Address>,
+    signer: BotSigner,
+) -> Result<Address> {
+    if let Some(address) = configured {
+        let code = metrics::time_rpc("get_code", client.get_code(address, None)).await?;
+        if code != CLAIMEXECUTOR_DEPLOYED_BYTECODE {
+            return Err(eyre!(
+                "claim_executor {} does not have the ClaimExecutor code",
+                telemetry::checksum(&address)
+            ));
+        }
+        return Ok(address);
+    }
+    let deployer = Arc::new(SignerMiddleware::new(client.clone(), signer));
+    let executor = ClaimExecutor::deploy(deployer, ())?.send().await?;
+    info!(
+        address = %telemetry::checksum(&executor.address()),
+        "Deployed ClaimExecutor, set claim_executor to reuse it"
+    );
+    Ok(executor.address())
+}
+
+/// Calldata making the executor claim `times` rewards from `target`.
+pub fn claim_rewards(target: Address, times: u64) -> Vec<u8> {
+    ClaimRewardsCall {
+        target,
+        times: times.into(),
+    }
+    .encode()
+}
@@ -1,434 +1,1368 @@
 pub mod abi;
+pub mod bid;
+pub mod bundle;
+pub mod captures;
+pub mod challenges;
+pub mod cli;
+pub mod config;
 pub mod ctf;
+pub mod deploy;
+pub mod executor;
+pub mod metrics;
+pub mod preflight;
+pub mod registry;
+pub mod rules;
+pub mod scripts;
+pub mod signer;
+pub mod store;
+pub mod submit;
+pub mod telemetry;
+pub mod wallet;
 
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
-use dotenvy::{dotenv, var};
-use ethers::abi::AbiEncode;
+use dotenvy::dotenv;
+use ethers::abi::Token;
 use ethers::prelude::*;
 use ethers::types::transaction::eip2718::TypedTransaction;
-use eyre::Result;
-use futures_util::stream::FuturesUnordered;
+use eyre::{eyre, Result};
+use futures_util::stream::{FuturesOrdered, FuturesUnordered};
 use futures_util::StreamExt;
 use jsonrpsee::http_client::{transport::Error as HttpError, HttpClientBuilder};
-use mev_share::rpc::{
-    BundleItem, FlashbotsSignerLayer, Inclusion, MevApiClient, SendBundleRequest,
-};
+use mev_share::rpc::{FlashbotsSignerLayer, MevApiClient, SendBundleRequest, SimBundleResponse};
 use mev_share::sse::EventClient;
+use tokio::sync::watch;
 use tower::ServiceBuilder;
-use tracing::{info, warn};
-use tracing_subscriber::{filter::EnvFilter, fmt::Subscriber};
+use tracing::{debug, field, info, info_span, instrument, warn, Instrument, Span};
 
+use clap::Parser;
+
+use crate::bid::{Bid, BidEngine};
+use crate::bundle::{BundleBuilder, Bundling, Solution};
+use crate::captures::{CaptureKey, CaptureTracker};
+use crate::challenges::ChallengeMonitor;
+use crate::cli::{Cli, Command, ConfigCommand};
+use crate::config::Config;
 use crate::ctf::Flag;
-use abi::mev_share_ctf_simple::MevShareCTFSimpleCalls;
-use abi::mev_share_ctf_triple::MevShareCTFTripleCalls;
-use abi::mev_share_magic_number_v3::MevShareMagicNumberCalls;
-use abi::mev_share_new_contract::MevShareNewContractCalls;
+use crate::deploy::{new_contracts_deployment, DeployResolver, NewContractPair};
+use crate::executor::TripleStrategy;
+use crate::registry::{AbiRegistry, DecodedLog};
+use crate::rules::Rule;
+use crate::scripts::{Script, ScriptSolvers};
+use crate::signer::BotSigner;
+use crate::store::Store;
+use crate::submit::{Relay, SubmissionQueue};
+use crate::wallet::{Fees, WalletLease, WalletPool};
+use abi::mev_share_magic_number_v3::MevShareMagicNumberV3;
+
+//gas limit of every solution tx
+const SOLUTION_GAS: u64 = 690_420;
+//claims CTFTriple wants in one block
+const TRIPLE_CLAIMS: u64 = 3;
+//between block subscription attempts, doubling up to the max
+const HEAD_BACKOFF_MIN: Duration = Duration::from_millis(500);
+const HEAD_BACKOFF_MAX: Duration = Duration::from_secs(30);
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    //load env
-    dotenv()?;
-    let fb_signer = var("FlashbotKey")?.parse::<LocalWallet>()?;
-    let tx_signer = var("BotKey")?.parse::<LocalWallet>()?;
-    let goerli_endpoint = var("EthereumApi")?;
+    //load env, a missing .env is fine now config can come from a file
+    let _ = dotenv();
+    let cli = Cli::parse();
+    let config = Config::load(&cli)?;
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run(config).await,
+        Command::Attempts { challenge, limit } => {
+            let store = Store::open(&config.store_path)?;
+            cli::print_attempts(&store, challenge.as_deref(), limit)
+        }
+        Command::Contracts => cli::contracts_check(&config).await,
+        Command::Captures => {
+            let store = Store::open(&config.store_path)?;
+            cli::print_captures(&store)
+        }
+        Command::Config {
+            action: ConfigCommand::Check,
+        } => cli::config_check(&config).await,
+    }
+}
+
+async fn run(config: Config) -> Result<()> {
+    config.validate()?;
+    let fb_signer = config.flashbot_signer().await?;
+    let wallet_signers = config.wallet_signers().await?;
 
     //set up tracing
-    Subscriber::builder()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+    telemetry::init(config.log_format, config.otlp_endpoint.as_deref())?;
+    info!(?config, "Loaded config");
+    let store = Arc::new(Store::open(&config.store_path)?);
 
-    //mev-share-sse client
-    let goerli_sse = "https://mev-share-goerli.flashbots.net";
-    let event_client = EventClient::default();
-    let mut mev_share_stream = event_client.events(goerli_sse).await.unwrap();
-    info!("Subscribed to {}", mev_share_stream.endpoint());
+    //prometheus scrape endpoint
+    let metrics_addr = config.metrics_addr.parse()?;
+    tokio::spawn(async move {
+        if let Err(e) = metrics::serve(metrics_addr).await {
+            warn!("Metrics server stopped {:?}", e);
+        }
+    });
 
-    //mev-share-bundle-client
-    let signing_middleware = FlashbotsSignerLayer::new(fb_signer);
-    let service_builder = ServiceBuilder::new()
-        // map signer errors to http errors
-        .map_err(|e| HttpError::Http(e))
-        .layer(signing_middleware);
-    let url = "https://relay-goerli.flashbots.net:443";
-    let bundle_client = HttpClientBuilder::default()
-        .set_middleware(service_builder)
-        .build(url)
-        .expect("Failed to create http client");
-    let bundle_client = Arc::new(bundle_client);
+    //mev-share-bundle-client per relay
+    let mut relays = Vec::new();
+    for relay in &config.relays {
+        let signing_middleware = FlashbotsSignerLayer::new(fb_signer.clone());
+        let service_builder = ServiceBuilder::new()
+            // map signer errors to http errors
+            .map_err(|e| HttpError::Http(e))
+            .layer(signing_middleware);
+        let bundle_client = HttpClientBuilder::default()
+            .set_middleware(service_builder)
+            .build(&relay.url)
+            .expect("Failed to create http client");
+        relays.push(Relay {
+            name: relay.name.clone(),
+            client: Arc::new(bundle_client),
+            min_interval: Duration::from_millis(relay.min_interval_ms),
+        });
+    }
 
     //goerli-client
-    let ws = Ws::connect(goerli_endpoint).await?;
+    let ws = Ws::connect(config.ethereum_api()?).await?;
     let provider = Provider::new(ws);
     let client = Arc::new(provider);
+    config.check_chain_id(&client).await?;
 
-    //map of address -> contract flag type
-    let contracts = ctf::contracts();
+    //track chain head so queued bundles for passed blocks get dropped
+    let (head_tx, head_rx) = watch::channel(client.get_block_number().await?);
+    tokio::spawn(watch_head(client.clone(), head_tx));
 
-    while let Some(event) = mev_share_stream.next().await {
-        let event = event?;
-        info!("{:?}", event);
+    //every solver submits through this queue
+    let queue = SubmissionQueue::spawn(
+        relays,
+        config.max_in_flight,
+        head_rx.clone(),
+        store.clone(),
+        config.bundle_settings(),
+        client.clone(),
+    );
+    //each solve leases its own wallet
+    let wallets = WalletPool::spawn(
+        wallet_signers,
+        config.min_wallet_balance()?,
+        config.low_balance_alert()?,
+        head_rx.clone(),
+        client.clone(),
+    )
+    .await?;
 
-        //get contract address jank
-        let contract_address = get_contract_address(&event);
-        if let Err(e) = contract_address {
-            warn!("{:?}", e);
+    //map of address -> contract flag type
+    let mut contracts = ctf::contracts();
+    //a wrong or redeployed address would have us backrun the wrong thing, leave those out
+    //one log line per challenge rather than the cli table so json logs stay json
+    for check in preflight::check_contracts(&client, &contracts).await {
+        let owner = check.owner.map(|owner| telemetry::checksum(&owner));
+        if check.healthy() {
+            info!(
+                challenge = check.flag.name(),
+                address = %telemetry::checksum(&check.address),
+                owner = owner.as_deref(),
+                active_block = check.active_block.map(|block| block.as_u64()),
+                "Challenge contract ok"
+            );
             continue;
         }
-        let contract_address = contract_address.unwrap();
-
-        let client = client.clone();
-        let bundle_client = bundle_client.clone();
-        let tx_signer = tx_signer.clone();
-        match contracts.get(&contract_address) {
-            //this is fucking horrific lmao but i cba fighting with futures to refactor
-            Some(Flag::CTFSimple(false)) => {
-                tokio::spawn(async move {
-                    let res =
-                        solve_ctf_simple(event, contract_address, client, bundle_client, tx_signer)
-                            .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+        warn!(
+            challenge = check.flag.name(),
+            address = %telemetry::checksum(&check.address),
+            owner = owner.as_deref(),
+            active_block = check.active_block.map(|block| block.as_u64()),
+            problems = ?check.problems,
+            "Challenge contract misconfigured, ignoring its events"
+        );
+        contracts.remove(&check.address);
+    }
+    //activeBlock of each challenge, read every head
+    let challenges = ChallengeMonitor::spawn(&contracts, head_rx, client.clone()).await;
+    //the executor is the one calling claimReward so it may be what gets credited
+    let executor = match config.triple_strategy {
+        TripleStrategy::MultiTx => None,
+        TripleStrategy::Executor => Some(
+            executor::claim_executor(&client, config.claim_executor, config.tx_signer().await?)
+                .await?,
+        ),
+    };
+    let mut searchers = wallets.addresses();
+    searchers.extend(executor);
+    //what the capture logger credits us with is what is done
+    let capture_logger = match config.capture_logger {
+        Some(logger) => logger,
+        None => captures::find_capture_logger(&client, &contracts).await?,
+    };
+    let captures = CaptureTracker::spawn(
+        client.clone(),
+        capture_logger,
+        contracts.clone(),
+        searchers,
+        store.clone(),
+        config.captures_from_block,
+    )
+    .await?;
+    //init code of contracts the challenges deploy
+    let mut init_code = ctf::init_code();
+    init_code.extend(config.init_code_overrides());
+    let resolver = Arc::new(DeployResolver::load(init_code)?);
+    let bids = Arc::new(config.bid_engine()?);
+    //ABIs are read at startup so a new challenge only needs its file and a registry entry
+    let abis = Arc::new(config.abi_registry()?);
+    for (address, entry) in abis.added() {
+        info!(
+            challenge = %entry.challenge,
+            address = %telemetry::checksum(address),
+            abi = %entry.abi,
+            "Registered challenge, its events are decoded but not solved"
+        );
+    }
+    let rules = config.rule_engine(&abis)?;
+    for rule in rules.rules() {
+        info!(
+            challenge = rule.flag.name(),
+            to = %telemetry::checksum(&rule.to),
+            txs = rule.txs,
+            "Loaded rule"
+        );
+    }
+    let scripts = config
+        .scripts_dir
+        .clone()
+        .map(|dir| ScriptSolvers::spawn(dir, client.clone(), abis.clone()));
+
+    //subscribe last so no hint arrives before the challenges have been checked
+    let event_client = EventClient::default();
+    let mut mev_share_stream = event_client.events(&config.sse_url).await?;
+    info!("Subscribed to {}", mev_share_stream.endpoint());
+
+    loop {
+        let event = match mev_share_stream.next().await {
+            Some(Ok(event)) => event,
+            Some(Err(e)) => {
+                warn!("SSE stream error {:?}", e);
+                continue;
             }
-            Some(Flag::CTFTriple(false)) => {
-                tokio::spawn(async move {
-                    let res =
-                        solve_ctf_triple(event, contract_address, client, bundle_client, tx_signer)
-                            .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+            None => {
+                warn!("SSE stream ended, resubscribing");
+                mev_share_stream = event_client.events(&config.sse_url).await?;
+                metrics::STREAM_RECONNECTS.inc();
+                continue;
             }
-            Some(Flag::MagicNumberV1(false)) => {
-                tokio::spawn(async move {
-                    let res = solve_magic_number(
-                        event,
-                        contract_address,
-                        client,
-                        bundle_client,
-                        tx_signer,
-                        Flag::MagicNumberV1(false),
-                    )
-                    .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+        };
+        let span = info_span!(
+            "event",
+            hash = %telemetry::hex(&event.hash),
+            challenge = field::Empty
+        );
+        let _enter = span.enter();
+        telemetry::log_event(&event);
+        metrics::event_received(event.hash);
+
+        //built in solvers go by the contract a hint points at, rules and scripts see every hint
+        let contract_address = get_contract_address(&event);
+        let mut flag = contract_address.and_then(|address| contracts.get(&address).copied());
+        let matched = match flag {
+            Some(_) => None,
+            None => rules.matching(&event),
+        };
+        let mut target = contract_address;
+        if let Some((rule, _)) = &matched {
+            //a rule's challenge is the contract it calls
+            flag = Some(rule.flag);
+            target = Some(rule.to);
+        } else if contract_address.is_none() {
+            //a hint hiding everything has nothing for a script to go on either
+            target = Some(ctf::hidden_hint_contract());
+            flag = contracts.get(&ctf::hidden_hint_contract()).copied();
+        }
+        if let Some(flag) = flag {
+            span.record("challenge", flag.name());
+            metrics::EVENTS_MATCHED
+                .with_label_values(&[flag.name()])
+                .inc();
+        } else if let Some(entry) = contract_address.and_then(|address| abis.entry(&address)) {
+            span.record("challenge", entry.challenge.as_str());
+            log_decoded(&abis, &event);
+        }
+        if let Err(e) = store.record_event(&event, contract_address, flag.as_ref()) {
+            warn!("Failed to record event {:?}", e);
+        }
+        if let (Some(flag), Some(address)) = (flag, target) {
+            //both NewContracts activations are flags, only skip the one we have
+            let kind = match flag {
+                Flag::NewContracts => deploy::hinted_kind(&event),
+                _ => None,
+            };
+            if captures.is_captured(&CaptureKey {
+                contract: address,
+                kind,
+            }) {
+                debug!("Challenge already captured, skipping");
+                continue;
             }
-            Some(Flag::MagicNumberV2(false)) => {
-                tokio::spawn(async move {
-                    let res = solve_magic_number(
-                        event,
+        }
+
+        let ctx = SolverContext {
+            client: client.clone(),
+            queue: queue.clone(),
+            wallets: wallets.clone(),
+            bids: bids.clone(),
+            abis: abis.clone(),
+        };
+        let retry = Retry {
+            challenges: challenges.clone(),
+            client: client.clone(),
+            contract_address: target,
+            target_hash: event.hash,
+            retries: config.activation_retries,
+        };
+        if let Some((rule, log)) = matched {
+            spawn_solver(
+                retry
+                    .run(move || solve_rule(event.clone(), rule.clone(), log.clone(), ctx.clone())),
+            );
+            continue;
+        }
+        if let (None, Some(scripts)) = (flag, &scripts) {
+            let scripts = scripts.clone();
+            //scripts are matched off the event loop, a slow one only holds up its own hint
+            spawn_solver(async move {
+                let Some(script) = scripts.matching(event.clone()).await else {
+                    return Ok(());
+                };
+                Span::current().record("challenge", script.flag.name());
+                metrics::EVENTS_MATCHED
+                    .with_label_values(&[script.flag.name()])
+                    .inc();
+                retry
+                    .run(move || {
+                        solve_script(event.clone(), script.clone(), scripts.clone(), ctx.clone())
+                    })
+                    .await
+            });
+            continue;
+        }
+        let Some(contract_address) = target else {
+            continue;
+        };
+        match flag {
+            Some(Flag::CTFSimple) => spawn_solver(
+                retry.run(move || solve_ctf_simple(event.clone(), contract_address, ctx.clone())),
+            ),
+            Some(Flag::CTFTriple) => spawn_solver(retry.run(move || {
+                solve_ctf_triple(event.clone(), contract_address, ctx.clone(), executor)
+            })),
+            Some(Flag::MagicNumberV3) => {
+                let reserve_attempts = config.magic_v3_reserve_attempts;
+                spawn_solver(retry.run(move || {
+                    solve_magic_number_v3(
+                        event.clone(),
                         contract_address,
-                        client,
-                        bundle_client,
-                        tx_signer,
-                        Flag::MagicNumberV2(false),
+                        ctx.clone(),
+                        reserve_attempts,
                     )
-                    .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+                }))
             }
-            Some(Flag::MagicNumberV3(false)) => {
-                tokio::spawn(async move {
-                    let res = solve_magic_number(
-                        event,
-                        contract_address,
-                        client,
-                        bundle_client,
-                        tx_signer,
-                        Flag::MagicNumberV3(false),
-                    )
-                    .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+            Some(flag @ Flag::MagicNumberV1) | Some(flag @ Flag::MagicNumberV2) => {
+                spawn_solver(retry.run(move || {
+                    solve_magic_number(event.clone(), contract_address, ctx.clone(), flag)
+                }))
             }
-            Some(Flag::NewContracts(false)) => {
-                tokio::spawn(async move {
-                    let res = solve_new_contracts(
-                        event,
+            Some(Flag::NewContracts) => {
+                let resolver = resolver.clone();
+                let captures = captures.clone();
+                spawn_solver(retry.run(move || {
+                    solve_new_contracts(
+                        event.clone(),
                         contract_address,
-                        client,
-                        bundle_client,
-                        tx_signer,
+                        ctx.clone(),
+                        resolver.clone(),
+                        captures.clone(),
                     )
-                    .await;
-                    match res {
-                        Ok(_) => (),
-                        Err(e) => warn!("{:?}", e),
-                    }
-                });
+                }))
             }
             _ => (),
         }
     }
-    Ok(())
 }
 
-fn get_contract_address(event: &mev_share::sse::Event) -> Result<Address> {
-    //pretty questionable but works for this ctf...
-    match event {
-        e if event.transactions.len() == 1 => {
-            if let Some(address) = e.transactions[0].to {
-                Ok(address)
-            } else {
-                Ok("0x20a1A5857fDff817aa1BD8097027a841D4969AA5"
-                    .parse::<Address>()
-                    .unwrap())
+/// Keep `head` at the latest block. Everything that waits on heads stalls without it,
+/// so a failed or ended subscription is retried with backoff rather than given up on.
+async fn watch_head(client: Arc<Provider<Ws>>, head: watch::Sender<U64>) {
+    let mut backoff = HEAD_BACKOFF_MIN;
+    loop {
+        match client.subscribe_blocks().await {
+            Ok(mut blocks) => {
+                //heads missed while resubscribing
+                if let Ok(number) = client.get_block_number().await {
+                    head.send_if_modified(|current| {
+                        let newer = number > *current;
+                        if newer {
+                            *current = number;
+                        }
+                        newer
+                    });
+                }
+                while let Some(block) = blocks.next().await {
+                    if let Some(number) = block.number {
+                        let _ = head.send(number);
+                        backoff = HEAD_BACKOFF_MIN;
+                    }
+                }
+                warn!("Block subscription ended, resubscribing");
+            }
+            Err(e) => warn!(?backoff, "Failed to subscribe to blocks {:?}", e),
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(HEAD_BACKOFF_MAX);
+    }
+}
+
+/// Run a solver in the background under the current event span.
+fn spawn_solver(solver: impl Future<Output = Result<()>> + Send + 'static) {
+    tokio::spawn(
+        async move {
+            if let Err(e) = solver.await {
+                warn!("{:?}", e);
+            }
+        }
+        .in_current_span(),
+    );
+}
+
+/// Handles every solver shares.
+struct SolverContext<C> {
+    client: Arc<Provider<Ws>>,
+    queue: Arc<SubmissionQueue<C>>,
+    wallets: Arc<WalletPool>,
+    bids: Arc<BidEngine>,
+    abis: Arc<AbiRegistry>,
+}
+
+//derive would want C: Clone
+impl<C> Clone for SolverContext<C> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            queue: self.queue.clone(),
+            wallets: self.wallets.clone(),
+            bids: self.bids.clone(),
+            abis: self.abis.clone(),
+        }
+    }
+}
+
+/// Resends a solve for the next block while the activation it backruns is still pending.
+struct Retry {
+    challenges: Arc<ChallengeMonitor>,
+    client: Arc<Provider<Ws>>,
+    contract_address: Option<Address>,
+    target_hash: TxHash,
+    retries: u64,
+}
+
+impl Retry {
+    /// `solve` returns the block it targeted. activeBlock read once that block is in says
+    /// whether the activation landed there unclaimed, otherwise the target tx says whether
+    /// it landed at all or is still pending and worth another go.
+    async fn run<F, Fut>(self, mut solve: F) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<U64>>,
+    {
+        for attempt in 0..=self.retries {
+            let target_block = solve().await?;
+            //challenges without activeBlock get a single go
+            let Some(address) = &self.contract_address else {
+                return Ok(());
+            };
+            let Some(state) = self.challenges.settled(address, target_block).await else {
+                return Ok(());
+            };
+            if state.claimable_in(target_block) {
+                warn!(%target_block, "Activation landed unclaimed, too late to retry");
+                return Ok(());
+            }
+            //a claim resets activeBlock so it looks the same as an activation still pending
+            let receipt = metrics::time_rpc(
+                "get_transaction_receipt",
+                self.client.get_transaction_receipt(self.target_hash),
+            )
+            .await?;
+            if let Some(receipt) = receipt {
+                info!(landed_in = ?receipt.block_number, active_block = %state.active_block, "Activation landed and was claimed");
+                return Ok(());
+            }
+            if attempt < self.retries {
+                info!(%target_block, read_at = %state.read_at, retry = attempt + 1, "Activation still pending, retrying next block");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hint logs of a registered challenge as its ABI reads them.
+fn log_decoded(abis: &AbiRegistry, event: &mev_share::sse::Event) {
+    for log in &event.logs {
+        let log = Log {
+            address: log.address,
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+            ..Default::default()
+        };
+        match abis.decode_log(&log) {
+            Ok(Some(decoded)) => {
+                info!(event = %decoded.event, params = ?decoded.params, "Decoded challenge log")
             }
+            Ok(None) => debug!(topics = ?log.topics, "Challenge log matches no event in its ABI"),
+            Err(e) => warn!("Failed to decode challenge log {:?}", e),
         }
-        e if event.logs.len() == 1 => Ok(e.logs[0].address),
-        _ => Ok("0x20a1A5857fDff817aa1BD8097027a841D4969AA5"
-            .parse::<Address>()
-            .unwrap()),
+    }
+}
+
+/// The one contract a hint points at, its tx `to` or else its log address.
+fn get_contract_address(event: &mev_share::sse::Event) -> Option<Address> {
+    if let [tx] = event.transactions.as_slice() {
+        if let Some(address) = tx.to {
+            return Some(address);
+        }
+    }
+    match event.logs.as_slice() {
+        [log] => Some(log.address),
+        _ => None,
     }
 }
 
 async fn solve_ctf_simple(
     event: mev_share::sse::Event,
     contract_address: Address,
-    client: Arc<Provider<Ws>>,
-    bundle_client: Arc<impl MevApiClient>,
-    tx_signer: LocalWallet,
-) -> Result<()> {
-    let data =
-        MevShareCTFSimpleCalls::ClaimReward(abi::mev_share_ctf_simple::ClaimRewardCall).encode();
-    let nonce = client
-        .get_transaction_count(tx_signer.address(), None)
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        abis,
+    } = ctx;
+    let data = abis.encode_call(&contract_address, "claimReward", &[])?;
+    let flag = Flag::CTFSimple;
+    let solve = Solve::prepare(&client, wallets.lease().await?, &bids, &flag, 1).await?;
+    let solution_bytes = solve
+        .sign(&client, contract_address, data, SOLUTION_GAS, solve.nonce)
         .await?;
-    let solution_bytes =
-        populate_solution_tx(contract_address, data, &client, &tx_signer, nonce.as_u64()).await?;
-    let block_number = client.get_block_number().await?;
+    let signed = solve.release(&client, &bids, 1).await?;
 
     send_solution_backrun(
         event.hash,
-        vec![solution_bytes],
-        bundle_client,
-        block_number,
-        &Flag::CTFSimple(false),
+        signed.with_payment(vec![Solution::required(solution_bytes)]),
+        queue,
+        signed.block_number,
+        &flag,
     )
     .await?;
-    Ok(())
+    Ok(signed.block_number + 1)
+}
+
+/// A `[[rules]]` call made `txs` times on consecutive nonces, arguments from the matched log.
+async fn solve_rule(
+    event: mev_share::sse::Event,
+    rule: Arc<Rule>,
+    log: Option<DecodedLog>,
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        ..
+    } = ctx;
+    let data = rule.calldata(log.as_ref())?;
+    let flag = rule.flag;
+    let solve = Solve::prepare_with_gas(
+        &client,
+        wallets.lease().await?,
+        &bids,
+        &flag,
+        rule.txs,
+        rule.total_gas(),
+        1,
+    )
+    .await?;
+    let mut solution_bytes = Vec::new();
+    for n in solve.nonce..(solve.nonce + rule.txs) {
+        solution_bytes.push(Solution::required(
+            solve
+                .sign(&client, rule.to, data.clone(), rule.gas, n)
+                .await?,
+        ));
+    }
+    let signed = solve.release(&client, &bids, rule.txs).await?;
+
+    send_solution_backrun(
+        event.hash,
+        signed.with_payment(solution_bytes),
+        queue,
+        signed.block_number,
+        &flag,
+    )
+    .await?;
+    Ok(signed.block_number + 1)
+}
+
+/// Whatever calls a script's `solve` returns, one tx each on consecutive nonces. Every stage
+/// after the first goes in a bundle of its own with the earlier stages nested in front of it.
+async fn solve_script(
+    event: mev_share::sse::Event,
+    script: Arc<Script>,
+    scripts: Arc<ScriptSolvers>,
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        ..
+    } = ctx;
+    let flag = script.flag;
+    let solution = scripts.solve(script, event.clone()).await?;
+    if solution.stages.is_empty() {
+        return Err(eyre!("{} solve returned no calls", flag.name()));
+    }
+    let solve = Solve::prepare_with_gas(
+        &client,
+        wallets.lease().await?,
+        &bids,
+        &flag,
+        solution.calls().count() as u64,
+        solution.calls().map(|call| call.gas).sum(),
+        solution.blocks,
+    )
+    .await?;
+    let block_number = solve.block_number;
+    let last_block = block_number + solution.blocks;
+    let stage_builder = || BundleBuilder::new(block_number + 1).max_block(last_block);
+
+    let mut builder = stage_builder().backrun(event.hash);
+    let mut nonces = solve.nonce..;
+    for (i, stage) in solution.stages.iter().enumerate() {
+        if i > 0 {
+            builder = stage_builder().nest(builder.build()?);
+        }
+        //stage first, so running out of calls does not take a nonce
+        for (call, n) in stage.iter().zip(nonces.by_ref()) {
+            let tx = solve
+                .sign(&client, call.to, call.data.clone(), call.gas, n)
+                .await?;
+            builder = builder.tx(tx, false);
+        }
+    }
+    let signed = solve
+        .release(&client, &bids, solution.calls().count() as u64)
+        .await?;
+    if let Some(payment) = signed.payment {
+        builder = builder.tx(payment, false);
+    }
+    if let Some(hints) = &solution.share {
+        builder = builder.share(hints);
+    }
+
+    send_bundle(builder.build()?, queue, &flag).await?;
+    Ok(last_block)
 }
 
+/// Three claims in one block, either three txs on consecutive nonces or one through `executor`.
 async fn solve_ctf_triple(
     event: mev_share::sse::Event,
     contract_address: Address,
-    client: Arc<Provider<Ws>>,
-    bundle_client: Arc<impl MevApiClient>,
-    tx_signer: LocalWallet,
-) -> Result<()> {
-    let data =
-        MevShareCTFTripleCalls::ClaimReward(abi::mev_share_ctf_triple::ClaimRewardCall).encode();
-    let nonce = client
-        .get_transaction_count(tx_signer.address(), None)
-        .await?
-        .as_u64();
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+    executor: Option<Address>,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        abis,
+    } = ctx;
+    let data = abis.encode_call(&contract_address, "claimReward", &[])?;
+    let flag = Flag::CTFTriple;
+    if let Some(executor) = executor {
+        //one tx doing all three, nothing in between can take a nonce from under it
+        let solve = Solve::prepare_with_gas(
+            &client,
+            wallets.lease().await?,
+            &bids,
+            &flag,
+            1,
+            SOLUTION_GAS * TRIPLE_CLAIMS,
+            1,
+        )
+        .await?;
+        let solution_bytes = solve
+            .sign(
+                &client,
+                executor,
+                executor::claim_rewards(contract_address, TRIPLE_CLAIMS),
+                SOLUTION_GAS * TRIPLE_CLAIMS,
+                solve.nonce,
+            )
+            .await?;
+        let signed = solve.release(&client, &bids, 1).await?;
+        send_solution_backrun(
+            event.hash,
+            signed.with_payment(vec![Solution::required(solution_bytes)]),
+            queue,
+            signed.block_number,
+            &flag,
+        )
+        .await?;
+        return Ok(signed.block_number + 1);
+    }
+
+    //all three land together so all three have to be paid for
+    let solve =
+        Solve::prepare(&client, wallets.lease().await?, &bids, &flag, TRIPLE_CLAIMS).await?;
     let mut solution_bytes = Vec::new();
-    for n in nonce..(nonce + 3) {
-        solution_bytes.push(
-            populate_solution_tx(contract_address, data.clone(), &client, &tx_signer, n).await?,
-        );
+    for n in solve.nonce..(solve.nonce + TRIPLE_CLAIMS) {
+        solution_bytes.push(Solution::required(
+            solve
+                .sign(&client, contract_address, data.clone(), SOLUTION_GAS, n)
+                .await?,
+        ));
     }
-    let block_number = client.get_block_number().await?;
+    let signed = solve.release(&client, &bids, TRIPLE_CLAIMS).await?;
 
     send_solution_backrun(
         event.hash,
-        solution_bytes,
-        bundle_client,
-        block_number,
-        &Flag::CTFTriple(false),
+        signed.with_payment(solution_bytes),
+        queue,
+        signed.block_number,
+        &flag,
     )
     .await?;
-    Ok(())
+    Ok(signed.block_number + 1)
 }
 
 async fn solve_magic_number(
     mut event: mev_share::sse::Event,
     contract_address: Address,
-    client: Arc<Provider<Ws>>,
-    bundle_client: Arc<impl MevApiClient>,
-    tx_signer: LocalWallet,
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
     flag: Flag,
-) -> Result<()> {
-    let log = event.logs.pop().unwrap(); //hehe
-    let log = Log {
-        address: log.address,
-        topics: log.topics,
-        data: log.data,
-        ..Default::default()
-    };
-    let parsed: abi::mev_share_magic_number_v3::ActivateFilter = ethers::contract::parse_log(log)?;
-    let lower_bound = parsed.lower_bound.as_u64();
-    let upper_bound = parsed.upper_bound.as_u64();
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        abis,
+    } = ctx;
+    let (lower_bound, upper_bound) = magic_number_bounds(&mut event, &abis)?;
 
-    let nonce = client
-        .get_transaction_count(tx_signer.address(), None)
-        .await?
-        .as_u64();
-    let block_number = client.get_block_number().await?;
+    //guesses share a nonce so at most one lands, unless packed where they all go in one bundle
+    let bundling = queue.settings().bundling(&flag);
+    let guesses = upper_bound.saturating_sub(lower_bound);
+    let solve = Solve::prepare(
+        &client,
+        wallets.lease().await?,
+        &bids,
+        &flag,
+        bundling.nonces(guesses),
+    )
+    .await?;
 
     //doing this concurrently cause i query the rpc to fill transaction and its kinda slow otherwise
-    let mut futs = FuturesUnordered::new();
-    for m in lower_bound..upper_bound {
-        let data = MevShareMagicNumberCalls::ClaimReward(
-            abi::mev_share_magic_number_v3::ClaimRewardCall {
-                magic_number: U256::from(m),
-            },
-        )
-        .encode();
-        futs.push(populate_solution_tx(
+    let mut futs = FuturesOrdered::new();
+    for (i, m) in (lower_bound..upper_bound).enumerate() {
+        let data = abis.encode_call(
+            &contract_address,
+            "claimReward",
+            &[Token::Uint(U256::from(m))],
+        )?;
+        futs.push_back(solve.sign(
+            &client,
             contract_address,
             data,
-            &client,
-            &tx_signer,
-            nonce,
+            SOLUTION_GAS,
+            solve.nonce + bundling.nonce_offset(i),
         ));
     }
-    let mut solutions = Vec::new();
+    let mut candidates = Vec::new();
     while let Some(bytes) = futs.next().await {
-        solutions.push(bytes?);
+        candidates.push(bundling.solution(bytes?));
     }
+    drop(futs);
+    let signed = solve
+        .release(&client, &bids, bundling.nonces(guesses))
+        .await?;
 
-    //send backruns concurrently
-    let mut futs = FuturesUnordered::new();
-    for solution_bytes in solutions {
-        futs.push(send_solution_backrun(
-            event.hash,
-            vec![solution_bytes],
-            bundle_client.clone(),
-            block_number,
-            &flag,
-        ))
+    send_candidates(event.hash, candidates, bundling, &signed, queue, &flag).await;
+    Ok(signed.block_number + 1)
+}
+
+/// V3 only allows one landed guess per sender, so spend attempts on the simulated answer
+/// and only guess blind while more than `reserve_attempts` wallets still have theirs.
+async fn solve_magic_number_v3(
+    mut event: mev_share::sse::Event,
+    contract_address: Address,
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+    reserve_attempts: usize,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        abis,
+    } = ctx;
+    let flag = Flag::MagicNumberV3;
+    let (lower_bound, upper_bound) = magic_number_bounds(&mut event, &abis)?;
+
+    //registeredV3Attempts flips once a sender's guess lands
+    let contract = MevShareMagicNumberV3::new(contract_address, client.clone());
+    let mut fresh = Vec::new();
+    for address in wallets.addresses() {
+        let used = metrics::time_rpc(
+            "registered_v3_attempts",
+            contract.registered_v3_attempts(address).call(),
+        )
+        .await?;
+        if !used {
+            fresh.push(address);
+        }
     }
-    //wait for response from all backruns
-    while let Some(res) = futs.next().await {
-        if let Err(e) = res {
-            warn!("Error sending magicnumber spam backrun {:?}", e);
+    info!(attempts_left = fresh.len(), "MagicNumberV3 attempts");
+    if fresh.is_empty() {
+        return Err(eyre!("every wallet has spent its MagicNumberV3 attempt"));
+    }
+
+    //guesses share a nonce so at most one lands and burns the attempt, packing would burn more
+    let wallet = wallets
+        .lease_where(|address| fresh.contains(address))
+        .await?;
+    let solve = Solve::prepare(&client, wallet, &bids, &flag, 1).await?;
+    let mut futs = FuturesOrdered::new();
+    for m in lower_bound..upper_bound {
+        let data = abis.encode_call(
+            &contract_address,
+            "claimReward",
+            &[Token::Uint(U256::from(m))],
+        )?;
+        futs.push_back(solve.sign(&client, contract_address, data, SOLUTION_GAS, solve.nonce));
+    }
+    let mut guesses = Vec::new();
+    while let Some(bytes) = futs.next().await {
+        guesses.push(bytes?);
+    }
+    drop(futs);
+    let signed = solve.release(&client, &bids, 1).await?;
+
+    //only a correct claim makes it through to the capture logger
+    let found = find_verified(
+        &queue,
+        flag,
+        event.hash,
+        signed.block_number,
+        &guesses,
+        Bundling::PerCandidate,
+        |logs| !logs.is_empty(),
+    )
+    .await;
+    match found {
+        Some(i) => {
+            info!("Simulation found the magic number");
+            send_solution_backrun(
+                event.hash,
+                signed.with_payment(vec![Solution::required(guesses[i].clone())]),
+                queue,
+                signed.block_number,
+                &flag,
+            )
+            .await?;
+        }
+        None if fresh.len() > reserve_attempts => {
+            warn!(
+                attempts_left = fresh.len(),
+                "No guess verified in simulation, spending an attempt on a blind guess"
+            );
+            let candidates = guesses.into_iter().map(Solution::required).collect();
+            send_candidates(
+                event.hash,
+                candidates,
+                Bundling::PerCandidate,
+                &signed,
+                queue,
+                &flag,
+            )
+            .await;
+        }
+        None => {
+            return Err(eyre!(
+                "no guess verified in simulation, keeping the last {} MagicNumberV3 attempts",
+                fresh.len()
+            ))
         }
     }
+    Ok(signed.block_number + 1)
+}
 
-    Ok(())
+/// Simulate candidates on top of the target, one bundle each or all packed into one, and return
+/// the first that succeeds with its tx logs passing `verified`. One bundle each skips the queue
+/// so a long run of them is not held up behind it and the relay's rate limit.
+async fn find_verified(
+    queue: &SubmissionQueue<impl MevApiClient + Send + Sync + 'static>,
+    flag: Flag,
+    target_hash: TxHash,
+    block_number: U64,
+    candidates: &[Bytes],
+    bundling: Bundling,
+    verified: impl Fn(&[Log]) -> bool,
+) -> Option<usize> {
+    //tx logs of a candidate, the target's come first
+    let logs = |sim: &SimBundleResponse, i: usize| {
+        sim.logs
+            .as_ref()
+            .and_then(|logs| logs.get(1 + i))
+            .and_then(|logs| logs.tx_logs.clone())
+            .unwrap_or_default()
+    };
+    match bundling {
+        Bundling::Packed => {
+            let bundle = candidates
+                .iter()
+                .fold(
+                    BundleBuilder::new(block_number + 1).backrun(target_hash),
+                    |builder, tx| builder.tx(tx.clone(), true),
+                )
+                .build()
+                .ok()?;
+            match queue.simulate(flag, bundle).await {
+                Ok(sim) => (0..candidates.len()).find(|i| verified(&logs(&sim, *i))),
+                Err(e) => {
+                    warn!("Failed to simulate packed candidates {:?}", e);
+                    None
+                }
+            }
+        }
+        Bundling::PerCandidate => {
+            let mut futs = FuturesUnordered::new();
+            for (i, candidate) in candidates.iter().enumerate() {
+                let bundle = BundleBuilder::new(block_number + 1)
+                    .backrun(target_hash)
+                    .tx(candidate.clone(), false)
+                    .build()
+                    .ok()?;
+                futs.push(async move { (i, queue.simulate_now(bundle).await) });
+            }
+            while let Some((i, sim)) = futs.next().await {
+                match sim {
+                    Ok(sim) if sim.success && verified(&logs(&sim, 0)) => return Some(i),
+                    Ok(_) => (),
+                    Err(e) => warn!("Failed to simulate candidate {:?}", e),
+                }
+            }
+            None
+        }
+    }
+}
+
+fn magic_number_bounds(
+    event: &mut mev_share::sse::Event,
+    abis: &AbiRegistry,
+) -> Result<(u64, u64)> {
+    let log = event
+        .logs
+        .pop()
+        .ok_or_else(|| eyre!("magic number event has no Activate log"))?;
+    let log = Log {
+        address: log.address,
+        topics: log.topics,
+        data: log.data,
+        ..Default::default()
+    };
+    let activate = abis
+        .decode_log(&log)?
+        .filter(|decoded| decoded.event == "Activate")
+        .ok_or_else(|| eyre!("magic number event log is not Activate"))?;
+    let bound = |name| {
+        activate
+            .param(name)
+            .and_then(|value| value.clone().into_uint())
+            .map(|value| value.as_u64())
+            .ok_or_else(|| eyre!("Activate log has no {}", name))
+    };
+    Ok((bound("lowerBound")?, bound("upperBound")?))
 }
 
 async fn solve_new_contracts(
     mut event: mev_share::sse::Event,
     contract_address: Address,
-    client: Arc<Provider<Ws>>,
-    bundle_client: Arc<impl MevApiClient>,
-    tx_signer: LocalWallet,
-) -> Result<()> {
-    let log = event.logs.pop().unwrap(); //hehe
-    let log = Log {
+    ctx: SolverContext<impl MevApiClient + Send + Sync + 'static>,
+    resolver: Arc<DeployResolver>,
+    captures: Arc<CaptureTracker>,
+) -> Result<U64> {
+    let SolverContext {
+        client,
+        queue,
+        wallets,
+        bids,
+        abis,
+    } = ctx;
+    let log = event.logs.pop().map(|log| Log {
         address: log.address,
         topics: log.topics,
         data: log.data,
         ..Default::default()
-    };
+    });
 
-    //find new contract address
-    let new_contract_address: Address;
-    let topic0 = log.topics[0];
-    if format!("{:?}", topic0) //format! for ethers quirks
-        == String::from("0xf7e9fe69e1d05372bc855b295bc4c34a1a0a5882164dd2b26df30a26c1c8ba15")
-    {
-        let parsed: abi::mev_share_new_contracts::ActivateFilter =
-            ethers::contract::parse_log(log)?;
-        new_contract_address = parsed.newly_deployed_contract;
-    } else {
-        let parsed: abi::mev_share_new_contracts::ActivateBySaltFilter =
-            ethers::contract::parse_log(log)?;
-        let salt = parsed.salt;
-        let bytecode = ethers::utils::hex::decode("60a060405233608052436000556080516101166100266000396000606f01526101166000f3fe6080604052348015600f57600080fd5b506004361060325760003560e01c806396b81609146037578063b88a802f146051575b600080fd5b603f60005481565b60405190815260200160405180910390f35b60576059565b005b4360005414606657600080fd5b600080819055507f00000000000000000000000000000000000000000000000000000000000000006001600160a01b031663720ecf456040518163ffffffff1660e01b8152600401600060405180830381600087803b15801560c757600080fd5b505af115801560da573d6000803e3d6000fd5b5050505056fea26469706673582212207a00db890eff47285ac0d9c9b8735727d476952aa87b45ee82fd6bb4f42c6fa764736f6c63430008130033").unwrap();
-        new_contract_address = ethers::utils::get_create2_address(contract_address, salt, bytecode);
-    }
-    let data = MevShareNewContractCalls::ClaimReward(abi::mev_share_new_contract::ClaimRewardCall)
-        .encode();
-    let nonce = client
-        .get_transaction_count(tx_signer.address(), None)
+    //find new contract address, several candidates if the deployer nonce is hidden
+    let deployment = new_contracts_deployment(log)?;
+    let candidates = resolver
+        .candidates(&client, contract_address, deployment)
         .await?;
-    let solution_bytes = populate_solution_tx(
-        new_contract_address,
-        data,
+    let data = abis.encode(
+        abi::Contract::MevShareNewContract.file_stem(),
+        "claimReward",
+        &[],
+    )?;
+    let flag = Flag::NewContracts;
+    let mut bundling = queue.settings().bundling(&flag);
+    //funded for every candidate, per candidate bundles may have to fall back to packing them
+    let solve = Solve::prepare(
         &client,
-        &tx_signer,
-        nonce.as_u64(),
+        wallets.lease().await?,
+        &bids,
+        &flag,
+        candidates.len() as u64,
     )
     .await?;
-    let block_number = client.get_block_number().await?;
-    send_solution_backrun(
+    let mut solutions = sign_candidates(&solve, &client, &candidates, &data, bundling).await?;
+    //a claim on a candidate that was never deployed is a call to no code and succeeds, so
+    //unverified candidates on one nonce could land a wrong one. packed, all of them land
+    let packed = match bundling {
+        Bundling::PerCandidate if candidates.len() > 1 => {
+            let packed =
+                sign_candidates(&solve, &client, &candidates, &data, Bundling::Packed).await?;
+            let payment = solve
+                .payment(&client, &bids, candidates.len() as u64)
+                .await?;
+            Some((packed, payment))
+        }
+        _ => None,
+    };
+    let mut signed = solve
+        .release(&client, &bids, bundling.nonces(candidates.len() as u64))
+        .await?;
+
+    //the claim only counts if the child's proxyRegisterCapture gets the parent to register it
+    let txs: Vec<Bytes> = solutions.iter().map(|s| s.tx.clone()).collect();
+    let verified = find_verified(
+        &queue,
+        flag,
         event.hash,
-        vec![solution_bytes],
-        bundle_client,
-        block_number,
-        &Flag::NewContracts(false),
+        signed.block_number,
+        &txs,
+        bundling,
+        |logs| captures.registers(logs, &contract_address),
     )
-    .await?;
-    Ok(())
+    .await;
+    let pair = match verified {
+        Some(i) => {
+            info!(child = %telemetry::checksum(&candidates[i]), "Simulation registered the capture on the parent");
+            if bundling == Bundling::PerCandidate {
+                solutions = vec![solutions.swap_remove(i)];
+            }
+            Some(candidates[i])
+        }
+        None => match packed {
+            Some((packed, payment)) => {
+                warn!("No candidate registered a capture in simulation, sending them packed");
+                solutions = packed;
+                signed.payment = payment;
+                bundling = Bundling::Packed;
+                None
+            }
+            None => {
+                warn!("No candidate registered a capture in simulation, sending them unverified");
+                match candidates[..] {
+                    [child] => Some(child),
+                    _ => None,
+                }
+            }
+        },
+    }
+    .map(|child| NewContractPair {
+        parent: contract_address,
+        child,
+    });
+    send_candidates(event.hash, solutions, bundling, &signed, queue, &flag).await;
+    let target_block = signed.block_number + 1;
+
+    let Some(pair) = pair else {
+        return Ok(target_block);
+    };
+    //salt deployments are a prediction, check it against the chain once the target lands
+    if !DeployResolver::verify_deployed(client.clone(), pair.child, 3).await? {
+        warn!(
+            address = %telemetry::checksum(&pair.child),
+            ?deployment,
+            "No code at predicted new contract address"
+        );
+        return Ok(target_block);
+    }
+    //a claim resets the child's activeBlock, left at the target block means nobody claimed it
+    let active_block = pair.child_active_block(&client).await?;
+    if active_block == target_block {
+        warn!(child = %telemetry::checksum(&pair.child), %active_block, "Child activated in the target block but not claimed");
+    } else if active_block.is_zero() {
+        info!(child = %telemetry::checksum(&pair.child), "Child claimed");
+    } else {
+        info!(child = %telemetry::checksum(&pair.child), %active_block, %target_block, "Child activated outside the target block");
+    }
+    Ok(target_block)
+}
+
+/// A claim on each candidate, sharing a nonce or on consecutive ones as `bundling` has it.
+async fn sign_candidates(
+    solve: &Solve,
+    client: &Arc<Provider<Ws>>,
+    candidates: &[Address],
+    data: &[u8],
+    bundling: Bundling,
+) -> Result<Vec<Solution>> {
+    let mut futs = FuturesOrdered::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        futs.push_back(solve.sign(
+            client,
+            *candidate,
+            data.to_vec(),
+            SOLUTION_GAS,
+            solve.nonce + bundling.nonce_offset(i),
+        ));
+    }
+    let mut solutions = Vec::new();
+    while let Some(bytes) = futs.next().await {
+        solutions.push(bundling.solution(bytes?));
+    }
+    Ok(solutions)
+}
+
+/// Everything a solver needs to sign its txs: a wallet, nonces, the target block and
+/// fees that bid and fit the wallet balance.
+struct Solve {
+    wallet: WalletLease,
+    nonce: u64,
+    block_number: U64,
+    fees: Fees,
+    bid: Bid,
+}
+
+impl Solve {
+    /// For a bundle of `txs` solution txs signed with consecutive nonces.
+    async fn prepare(
+        client: &Arc<Provider<Ws>>,
+        wallet: WalletLease,
+        bids: &BidEngine,
+        flag: &Flag,
+        txs: u64,
+    ) -> Result<Self> {
+        Self::prepare_with_gas(client, wallet, bids, flag, txs, SOLUTION_GAS * txs, 1).await
+    }
+
+    /// Like `prepare` for txs that together use `gas` rather than `SOLUTION_GAS` each,
+    /// in a bundle that may land in any of the next `blocks` blocks.
+    async fn prepare_with_gas(
+        client: &Arc<Provider<Ws>>,
+        wallet: WalletLease,
+        bids: &BidEngine,
+        flag: &Flag,
+        txs: u64,
+        gas: u64,
+        blocks: u64,
+    ) -> Result<Self> {
+        let block_number = client.get_block_number().await?;
+        let bid = bids.bid(flag, block_number + 1, gas);
+        let nonce = wallet.bundle_nonce(client.as_ref()).await?;
+        let fees = wallet
+            .fund(client.as_ref(), gas, block_number + blocks, &bid)
+            .await?;
+        Ok(Self {
+            wallet,
+            nonce,
+            block_number,
+            fees,
+            bid,
+        })
+    }
+
+    /// A solution tx from the leased wallet at these fees.
+    async fn sign(
+        &self,
+        client: &Arc<Provider<Ws>>,
+        to: Address,
+        data: Vec<u8>,
+        gas: u64,
+        nonce: u64,
+    ) -> Result<Bytes> {
+        populate_tx(
+            to,
+            data,
+            gas,
+            client,
+            self.wallet.signer(),
+            nonce,
+            self.fees,
+        )
+        .await
+    }
+
+    /// The bid's coinbase payment, if any, to go after `txs` solution txs.
+    async fn payment(
+        &self,
+        client: &Arc<Provider<Ws>>,
+        bids: &BidEngine,
+        txs: u64,
+    ) -> Result<Option<Bytes>> {
+        //the payment goes last so it only pays if every solution tx made it in
+        bids.coinbase_transfer_tx(
+            &self.bid,
+            client.as_ref(),
+            self.wallet.signer(),
+            self.nonce + txs,
+            self.fees,
+        )
+        .await
+    }
+
+    /// Sign the payment for `txs` solution txs and give the wallet back. Funds are reserved
+    /// and nonces come from the chain, so the next solve can have it while this one
+    /// simulates and waits on relays.
+    async fn release(
+        self,
+        client: &Arc<Provider<Ws>>,
+        bids: &BidEngine,
+        txs: u64,
+    ) -> Result<Signed> {
+        let payment = self.payment(client, bids, txs).await?;
+        Ok(Signed {
+            block_number: self.block_number,
+            payment,
+        })
+    }
+}
+
+/// What is left of a `Solve` once everything is signed.
+struct Signed {
+    block_number: U64,
+    payment: Option<Bytes>,
+}
+
+impl Signed {
+    fn with_payment(&self, mut solutions: Vec<Solution>) -> Vec<Solution> {
+        solutions.extend(self.payment.clone().map(Solution::required));
+        solutions
+    }
+}
+
+/// Alternative solutions, one bundle each sharing a nonce or all packed into one bundle.
+async fn send_candidates(
+    target_hash: TxHash,
+    candidates: Vec<Solution>,
+    bundling: Bundling,
+    signed: &Signed,
+    queue: Arc<SubmissionQueue<impl MevApiClient + Send + Sync + 'static>>,
+    flag: &Flag,
+) {
+    let bundles = match bundling {
+        Bundling::Packed => vec![signed.with_payment(candidates)],
+        Bundling::PerCandidate => candidates
+            .into_iter()
+            .map(|candidate| signed.with_payment(vec![candidate]))
+            .collect(),
+    };
+    //send backruns concurrently and wait for response from all of them
+    let mut futs = FuturesUnordered::new();
+    for solutions in bundles {
+        futs.push(send_solution_backrun(
+            target_hash,
+            solutions,
+            queue.clone(),
+            signed.block_number,
+            flag,
+        ));
+    }
+    while let Some(res) = futs.next().await {
+        if let Err(e) = res {
+            warn!("Error sending {} backrun {:?}", flag.name(), e);
+        }
+    }
 }
 
 async fn send_solution_backrun(
     target_hash: TxHash,
-    solutions: Vec<Bytes>,
-    bundle_client: Arc<impl MevApiClient>,
+    solutions: Vec<Solution>,
+    queue: Arc<SubmissionQueue<impl MevApiClient + Send + Sync + 'static>>,
     block_number: U64,
     flag: &Flag,
 ) -> Result<()> {
-    let mut bundle_body = Vec::new();
-    bundle_body.push(BundleItem::Hash { hash: target_hash });
-    for solution in solutions {
-        bundle_body.push(BundleItem::Tx {
-            tx: solution,
-            can_revert: false,
-        });
-    }
+    let bundle = solutions
+        .into_iter()
+        .fold(
+            BundleBuilder::new(block_number + 1).backrun(target_hash),
+            |builder, solution| builder.tx(solution.tx, solution.can_revert),
+        )
+        .build()?;
+    send_bundle(bundle, queue, flag).await
+}
 
-    let bundle = SendBundleRequest {
-        bundle_body,
-        inclusion: Inclusion {
-            max_block: None,
-            block: block_number + 1,
-        },
-        ..Default::default()
-    };
-    info!("Sending {:?} bundle: {:?}", flag, bundle);
-    let resp = bundle_client.send_bundle(bundle).await;
-    info!("Sent {:?} bundle: {:?}", flag, resp);
-    // Simulate bundle
+/// Send a built bundle through the queue and wait for the relays to answer.
+async fn send_bundle(
+    bundle: SendBundleRequest,
+    queue: Arc<SubmissionQueue<impl MevApiClient + Send + Sync + 'static>>,
+    flag: &Flag,
+) -> Result<()> {
+    //the queue logs relay responses and drops itself
+    queue.submit(*flag, bundle).await?.await?;
     Ok(())
 }
 
-async fn populate_solution_tx(
+#[instrument(name = "populate_tx", skip(data, client, tx_signer))]
+async fn populate_tx(
     contract_address: Address,
     data: Vec<u8>,
+    gas: u64,
     client: &Arc<Provider<Ws>>,
-    tx_signer: &LocalWallet,
+    tx_signer: &BotSigner,
     nonce: u64,
+    fees: Fees,
 ) -> Result<Bytes> {
     let mut solution_tx: TypedTransaction = Eip1559TransactionRequest::new()
         .from(tx_signer.address())
         .to(contract_address)
         .data(data)
-        .gas(690_420)
+        .gas(gas)
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
         .nonce(nonce)
-        .chain_id(5)
+        .chain_id(tx_signer.chain_id())
         .value(0)
         .into();
-    client.fill_transaction(&mut solution_tx, None).await?; //this makes whole fn slow probably should have just hardcoded gas prices
+    metrics::time_rpc(
+        "fill_transaction",
+        client.fill_transaction(&mut solution_tx, None),
+    )
+    .await?; //fees come from the funding check so this no longer has to estimate them
     let signature = tx_signer
         .sign_transaction(&solution_tx.clone().into())
+        .instrument(info_span!("sign_tx"))
         .await?;
     let solution_bytes = solution_tx.rlp_signed(&signature);
     Ok(solution_bytes)
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use ethers::types::H256;
+use eyre::Result;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use mev_share::rpc::SendBundleRequest;
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_gauge_vec, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge_vec, Encoder, GaugeVec, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec, TextEncoder,
+};
+use tracing::info;
+
+use crate::bundle;
+use crate::ctf::Flag;
+
+pub static EVENTS_RECEIVED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("mevshare_events_received_total", "SSE events received").unwrap()
+});
+
+pub static EVENTS_MATCHED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_events_matched_total",
+        "SSE events matched to a challenge",
+        &["challenge"]
+    )
+    .unwrap()
+});
+
+pub static BUNDLES_BUILT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_bundles_built_total",
+        "Bundles pushed onto the submission queue",
+        &["challenge"]
+    )
+    .unwrap()
+});
+
+pub static BUNDLES_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_bundles_dropped_total",
+        "Bundles dropped because their target block passed",
+        &["challenge"]
+    )
+    .unwrap()
+});
+
+pub static BUNDLES_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_bundles_sent_total",
+        "Bundles accepted by a relay",
+        &["relay", "challenge"]
+    )
+    .unwrap()
+});
+
+pub static BUNDLES_FAILED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_bundles_failed_total",
+        "Bundles a relay returned an error for",
+        &["relay", "challenge"]
+    )
+    .unwrap()
+});
+
+pub static SOLVER_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "mevshare_solver_latency_seconds",
+        "Time from receiving an event to sending a bundle for it",
+        &["challenge"],
+        vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+    )
+    .unwrap()
+});
+
+pub static RPC_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "mevshare_rpc_latency_seconds",
+        "Latency of execution client rpc calls",
+        &["method"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]
+    )
+    .unwrap()
+});
+
+pub static STREAM_RECONNECTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "mevshare_stream_reconnects_total",
+        "Times the SSE stream was resubscribed"
+    )
+    .unwrap()
+});
+
+pub static WALLET_BALANCE: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "mevshare_wallet_balance_ether",
+        "Balance of each searcher wallet",
+        &["address"]
+    )
+    .unwrap()
+});
+
+pub static LOW_BALANCE_ALERTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_low_balance_alerts_total",
+        "Times a wallet dropped below the low balance threshold",
+        &["address"]
+    )
+    .unwrap()
+});
+
+pub static CHALLENGE_ACTIVE_BLOCK: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "mevshare_challenge_active_block",
+        "activeBlock of each challenge contract as of the latest head",
+        &["challenge", "address"]
+    )
+    .unwrap()
+});
+
+pub static FLAGS_CAPTURED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mevshare_flags_captured_total",
+        "Challenge contracts the capture logger credited one of our searchers with",
+        &["challenge"]
+    )
+    .unwrap()
+});
+
+//event hash -> when we saw it, for solver latency
+static RECEIVED_AT: Lazy<Mutex<HashMap<H256, Instant>>> = Lazy::new(Default::default);
+const RECEIVED_AT_TTL: Duration = Duration::from_secs(60);
+
+pub fn event_received(hash: H256) {
+    EVENTS_RECEIVED.inc();
+    let now = Instant::now();
+    let mut received_at = RECEIVED_AT.lock().unwrap();
+    //unmatched events never get a bundle so prune them here
+    if received_at.len() > 1024 {
+        received_at.retain(|_, at| now.duration_since(*at) < RECEIVED_AT_TTL);
+    }
+    received_at.insert(hash, now);
+}
+
+pub fn bundle_sending(flag: &Flag, bundle: &SendBundleRequest) {
+    let target = bundle::target(bundle);
+    let received_at = target.and_then(|hash| RECEIVED_AT.lock().unwrap().get(&hash).copied());
+    if let Some(at) = received_at {
+        SOLVER_LATENCY
+            .with_label_values(&[flag.name()])
+            .observe(at.elapsed().as_secs_f64());
+    }
+}
+
+/// Time an rpc future under `method`.
+pub async fn time_rpc<T>(method: &str, fut: impl Future<Output = T>) -> T {
+    let _timer = RPC_LATENCY.with_label_values(&[method]).start_timer();
+    fut.await
+}
+
+/// Serve the default registry on `/metrics` for prometheus to scrape.
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+    let make_svc =
+        make_service_fn(|_conn| async { Ok::<_, Infallible>(service_fn(handle_scrape)) });
+    info!("Serving metrics on http://{}/metrics", addr);
+    Server::try_bind(&addr)?.serve(make_svc).await?;
+    Ok(())
+}
+
+async fn handle_scrape(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.uri().path() != "/metrics" {
+        return Ok(Response::builder().status(404).body(Body::empty()).unwrap());
+    }
+    let encoder = TextEncoder::new();
+    let mut buf = Vec::new();
+    encoder.encode(&prometheus::gather(), &mut buf).unwrap();
+    Ok(Response::builder()
+        .header("Content-Type", encoder.format_type())
+        .body(Body::from(buf))
+        .unwrap())
+}
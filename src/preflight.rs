@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ethers::contract::EthCall;
+use ethers::prelude::*;
+use eyre::{eyre, Result};
+
+use crate::abi::{
+    mev_share_ctf_simple as simple, mev_share_ctf_triple as triple,
+    mev_share_magic_number_v3 as magic, mev_share_new_contracts as new_contracts,
+};
+use crate::challenges;
+use crate::ctf::Flag;
+use crate::metrics;
+
+/// What startup found at one registered challenge address.
+#[derive(Debug)]
+pub struct ContractCheck {
+    pub address: Address,
+    pub flag: Flag,
+    pub owner: Option<Address>,
+    pub active_block: Option<U64>,
+    pub problems: Vec<String>,
+}
+
+impl ContractCheck {
+    pub fn healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check every registered address has code with the functions its kind of challenge should have
+/// and that its views decode the way the bindings expect, sorted by challenge.
+pub async fn check_contracts(
+    client: &Arc<Provider<Ws>>,
+    contracts: &HashMap<Address, Flag>,
+) -> Vec<ContractCheck> {
+    let mut checks = Vec::new();
+    for (address, flag) in contracts {
+        checks.push(check_contract(client, *address, *flag).await);
+    }
+    checks.sort_by_key(|check| (check.flag.name(), check.address));
+    checks
+}
+
+async fn check_contract(client: &Arc<Provider<Ws>>, address: Address, flag: Flag) -> ContractCheck {
+    let mut check = ContractCheck {
+        address,
+        flag,
+        owner: None,
+        active_block: None,
+        problems: Vec::new(),
+    };
+    let code = match metrics::time_rpc("get_code", client.get_code(address, None)).await {
+        Ok(code) => code,
+        Err(e) => {
+            check.problems.push(format!("get_code failed: {}", e));
+            return check;
+        }
+    };
+    if code.is_empty() {
+        check.problems.push(String::from("no code at address"));
+        return check;
+    }
+    //the dispatcher compares against every external selector so each has to be in the code
+    for (name, selector) in selectors(&flag) {
+        if !code.windows(4).any(|window| window == selector) {
+            check
+                .problems
+                .push(format!("{} selector not in code", name));
+        }
+    }
+    match owner(client, address, &flag).await {
+        Ok(owner) => check.owner = Some(owner),
+        Err(e) => check.problems.push(format!("owner() failed: {}", e)),
+    }
+    if !matches!(flag, Flag::NewContracts) {
+        match challenges::active_block(client, address, &flag).await {
+            Ok(active_block) => check.active_block = Some(active_block),
+            Err(e) => check.problems.push(format!("activeBlock() failed: {}", e)),
+        }
+    }
+    check
+}
+
+/// Functions the bot relies on for each kind of challenge. The NewContracts deployer only
+/// activates, claimReward and activeBlock live on the contracts it deploys.
+fn selectors(flag: &Flag) -> Vec<(&'static str, Selector)> {
+    match flag {
+        Flag::CTFSimple => vec![
+            ("claimReward", simple::ClaimRewardCall::selector()),
+            ("activeBlock", simple::ActiveBlockCall::selector()),
+            ("owner", simple::OwnerCall::selector()),
+            (
+                "activateRewardSimple",
+                simple::ActivateRewardSimpleCall::selector(),
+            ),
+        ],
+        Flag::CTFTriple => vec![
+            ("claimReward", triple::ClaimRewardCall::selector()),
+            ("activeBlock", triple::ActiveBlockCall::selector()),
+            ("owner", triple::OwnerCall::selector()),
+            (
+                "activateRewardTriple",
+                triple::ActivateRewardTripleCall::selector(),
+            ),
+        ],
+        Flag::MagicNumberV1 | Flag::MagicNumberV2 | Flag::MagicNumberV3 => vec![
+            ("claimReward", magic::ClaimRewardCall::selector()),
+            ("activeBlock", magic::ActiveBlockCall::selector()),
+            ("owner", magic::OwnerCall::selector()),
+            (
+                "activateRewardMagicNumber",
+                magic::ActivateRewardMagicNumberCall::selector(),
+            ),
+        ],
+        Flag::NewContracts => vec![
+            ("owner", new_contracts::OwnerCall::selector()),
+            (
+                "activateRewardNewContract",
+                new_contracts::ActivateRewardNewContractCall::selector(),
+            ),
+            (
+                "activateRewardBySalt",
+                new_contracts::ActivateRewardBySaltCall::selector(),
+            ),
+        ],
+        //rules are checked against their ABI when config loads, scripts check themselves
+        Flag::Rule(_) | Flag::Script(_) => vec![],
+    }
+}
+
+async fn owner(client: &Arc<Provider<Ws>>, address: Address, flag: &Flag) -> Result<Address> {
+    let client = client.clone();
+    let owner = match flag {
+        Flag::CTFSimple => {
+            let call = simple::MevShareCtfSimple::new(address, client).owner();
+            metrics::time_rpc("owner", call.call()).await?
+        }
+        Flag::CTFTriple => {
+            let call = triple::MevShareCtfTriple::new(address, client).owner();
+            metrics::time_rpc("owner", call.call()).await?
+        }
+        Flag::MagicNumberV1 | Flag::MagicNumberV2 | Flag::MagicNumberV3 => {
+            let call = magic::MevShareMagicNumberV3::new(address, client).owner();
+            metrics::time_rpc("owner", call.call()).await?
+        }
+        Flag::NewContracts => {
+            let call = new_contracts::MevShareNewContracts::new(address, client).owner();
+            metrics::time_rpc("owner", call.call()).await?
+        }
+        Flag::Rule(name) | Flag::Script(name) => return Err(eyre!("{} has no known owner", name)),
+    };
+    Ok(owner)
+}
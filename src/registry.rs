@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+use ethers::abi::{Abi, Event, RawLog, Token};
+use ethers::prelude::*;
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::abi::Contract;
+use crate::ctf;
+
+/// Read at startup when no abi dir is configured, skipped if missing.
+pub const DEFAULT_ABI_DIR: &str = "./abi";
+
+/// A challenge added from config, its events are decoded with the ABI it names.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryEntry {
+    /// name it shows up under in logs and the store
+    pub challenge: String,
+    /// file stem of its ABI in the abi dir
+    pub abi: String,
+}
+
+/// A log decoded against the ABI of the contract that emitted it.
+#[derive(Debug, Clone)]
+pub struct DecodedLog {
+    pub event: String,
+    pub params: Vec<(String, Token)>,
+}
+
+impl DecodedLog {
+    pub fn param(&self, name: &str) -> Option<&Token> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// ABIs compiled in plus whatever is on disk at startup, and which one each challenge address
+/// speaks, so a new challenge is an ABI file and a registry entry rather than a rebuild.
+pub struct AbiRegistry {
+    abis: HashMap<String, Abi>,
+    contracts: HashMap<Address, RegistryEntry>,
+}
+
+impl AbiRegistry {
+    /// Every `*.json` in `dir` keyed by file stem over the compiled in ones,
+    /// then the built in challenges and `entries`. With no `dir` configured
+    /// `DEFAULT_ABI_DIR` is read if it is there, the compiled in ABIs are enough to run.
+    pub fn load(dir: Option<&Path>, entries: &HashMap<Address, RegistryEntry>) -> Result<Self> {
+        let mut abis: HashMap<String, Abi> = Contract::ALL
+            .iter()
+            .map(|contract| (contract.file_stem().to_string(), contract.abi().clone()))
+            .collect();
+        let dir = dir.or_else(|| Some(Path::new(DEFAULT_ABI_DIR)).filter(|dir| dir.is_dir()));
+        let files = match dir {
+            Some(dir) => fs::read_dir(dir)
+                .wrap_err_with(|| format!("reading abi dir {:?}", dir))?
+                .collect::<std::io::Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        for file in files {
+            let path = file.path();
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let abi = load_abi(&path).wrap_err_with(|| format!("loading ABI {:?}", path))?;
+            abis.insert(name.to_string(), abi);
+        }
+        let mut registry = Self {
+            abis,
+            contracts: HashMap::new(),
+        };
+        for (address, flag) in ctf::contracts() {
+            if let Some(contract) = flag.contract() {
+                registry.register(
+                    address,
+                    RegistryEntry {
+                        challenge: flag.name().to_string(),
+                        abi: contract.file_stem().to_string(),
+                    },
+                )?;
+            }
+        }
+        for (address, entry) in entries {
+            if registry.contracts.contains_key(address) {
+                return Err(eyre!(
+                    "registry entry {:?} is already a built in challenge",
+                    address
+                ));
+            }
+            registry.register(*address, entry.clone())?;
+        }
+        Ok(registry)
+    }
+
+    fn register(&mut self, address: Address, entry: RegistryEntry) -> Result<()> {
+        if !self.abis.contains_key(&entry.abi) {
+            return Err(eyre!(
+                "{} at {:?} uses ABI {:?} which is not in the abi dir",
+                entry.challenge,
+                address,
+                entry.abi
+            ));
+        }
+        self.contracts.insert(address, entry);
+        Ok(())
+    }
+
+    pub fn abi(&self, name: &str) -> Result<&Abi> {
+        self.abis
+            .get(name)
+            .ok_or_else(|| eyre!("no ABI {:?} loaded", name))
+    }
+
+    pub fn entry(&self, address: &Address) -> Option<&RegistryEntry> {
+        self.contracts.get(address)
+    }
+
+    /// Challenges registered from config, the built in ones have their own solvers.
+    pub fn added(&self) -> impl Iterator<Item = (&Address, &RegistryEntry)> {
+        let builtin = ctf::contracts();
+        self.contracts
+            .iter()
+            .filter(move |(address, _)| !builtin.contains_key(address))
+    }
+
+    /// Calldata for `function` of ABI `abi`, overloads resolve to the first declared.
+    pub fn encode(&self, abi: &str, function: &str, args: &[Token]) -> Result<Vec<u8>> {
+        let function = self
+            .abi(abi)?
+            .function(function)
+            .wrap_err_with(|| format!("ABI {:?}", abi))?;
+        Ok(function.encode_input(args)?)
+    }
+
+    /// Calldata for `function` of the challenge registered at `address`.
+    pub fn encode_call(
+        &self,
+        address: &Address,
+        function: &str,
+        args: &[Token],
+    ) -> Result<Vec<u8>> {
+        let entry = self
+            .entry(address)
+            .ok_or_else(|| eyre!("{:?} is not registered", address))?;
+        self.encode(&entry.abi, function, args)
+    }
+
+    /// Decode `log` with ABI `abi`, `None` if none of its events has the log's topic0.
+    pub fn decode(&self, abi: &str, log: &Log) -> Result<Option<DecodedLog>> {
+        let Some(topic0) = log.topics.first() else {
+            return Ok(None);
+        };
+        let Some(event) = self
+            .abi(abi)?
+            .events()
+            .find(|event| !event.anonymous && event.signature() == *topic0)
+        else {
+            return Ok(None);
+        };
+        decode_event(event, log).map(Some)
+    }
+
+    /// Decode `log` with the ABI of the challenge that emitted it, `None` if it is not registered.
+    pub fn decode_log(&self, log: &Log) -> Result<Option<DecodedLog>> {
+        match self.entry(&log.address) {
+            Some(entry) => self.decode(&entry.abi, log),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Decode `log` as `event`, which it has to be an instance of.
+pub fn decode_event(event: &Event, log: &Log) -> Result<DecodedLog> {
+    let parsed = event.parse_log(RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    })?;
+    Ok(DecodedLog {
+        event: event.name.clone(),
+        params: parsed
+            .params
+            .into_iter()
+            .map(|param| (param.name, param.value))
+            .collect(),
+    })
+}
+
+/// A bare ABI array or a foundry/hardhat artifact with an `abi` field.
+fn load_abi(path: &Path) -> Result<Abi> {
+    let contents = fs::read_to_string(path)?;
+    let json: serde_json::Value = serde_json::from_str(&contents)?;
+    let abi = match json {
+        serde_json::Value::Object(mut artifact) => artifact
+            .remove("abi")
+            .ok_or_else(|| eyre!("artifact has no abi field"))?,
+        abi => abi,
+    };
+    Ok(serde_json::from_value(abi)?)
+}
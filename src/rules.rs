@@ -0,0 +1,548 @@
+use std::sync::Arc;
+
+use ethers::abi::token::{LenientTokenizer, Tokenizer};
+use ethers::abi::{Event, Function, Token};
+use ethers::prelude::*;
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::ctf::{self, Flag};
+use crate::registry::{decode_event, AbiRegistry, DecodedLog};
+
+/// One `[[rules]]` entry: which hints to backrun and the call that solves them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// challenge name, also the key into `bids` and `bundle_options`
+    pub name: String,
+    /// contract the hint has to touch, as a tx `to` or a log address
+    pub address: Option<Address>,
+    /// event name in `abi` or a `0x` topic0 one of the hint's logs has to carry
+    pub event: Option<String>,
+    /// `0x` function selector one of the hint's txs has to call
+    pub selector: Option<String>,
+    /// ABI to decode the log and encode the call with, the registry entry of `address` if unset
+    pub abi: Option<String>,
+    /// contract to call, `address` if unset
+    pub to: Option<Address>,
+    /// function to call
+    pub call: String,
+    /// `log.<field>` of the matched log or a literal, in the call's argument order
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// times the call is made, one tx each on consecutive nonces
+    #[serde(default = "default_txs")]
+    pub txs: u64,
+    /// gas limit of each tx
+    pub gas: Option<u64>,
+}
+
+fn default_txs() -> u64 {
+    1
+}
+
+enum Arg {
+    Log(String),
+    Value(Token),
+}
+
+/// A rule checked against the ABIs it names, ready to match hints.
+pub struct Rule {
+    pub flag: Flag,
+    address: Option<Address>,
+    topic0: Option<H256>,
+    selector: Option<[u8; 4]>,
+    //to decode the matched log with, unknown if only a topic0 not in the ABI was given
+    event: Option<Event>,
+    pub to: Address,
+    function: Function,
+    args: Vec<Arg>,
+    pub txs: u64,
+    pub gas: u64,
+}
+
+impl Rule {
+    fn build(config: &RuleConfig, abis: &AbiRegistry) -> Result<Self> {
+        if ctf::contracts()
+            .values()
+            .any(|flag| flag.name() == config.name)
+        {
+            return Err(eyre!("name is taken by a built in challenge"));
+        }
+        if config.address.is_none() && config.event.is_none() && config.selector.is_none() {
+            return Err(eyre!("needs an address, event or selector to match on"));
+        }
+        let abi = match (
+            &config.abi,
+            config.address.and_then(|address| abis.entry(&address)),
+        ) {
+            (Some(abi), _) => abis.abi(abi)?,
+            (None, Some(entry)) => abis.abi(&entry.abi)?,
+            (None, None) => return Err(eyre!("abi is not set and address is not registered")),
+        };
+        let to = config
+            .to
+            .or(config.address)
+            .ok_or_else(|| eyre!("to is not set and there is no address"))?;
+        let function = abi.function(&config.call)?.clone();
+
+        let (topic0, event) = match &config.event {
+            None => (None, None),
+            Some(topic0) if topic0.starts_with("0x") => {
+                let topic0: H256 = topic0
+                    .parse()
+                    .map_err(|_| eyre!("event {:?} is not a topic0", topic0))?;
+                let event = abi
+                    .events()
+                    .find(|event| event.signature() == topic0)
+                    .cloned();
+                (Some(topic0), event)
+            }
+            Some(name) => {
+                let event = abi.event(name)?.clone();
+                (Some(event.signature()), Some(event))
+            }
+        };
+        let selector = match &config.selector {
+            Some(selector) => Some(parse_selector(selector)?),
+            None => None,
+        };
+
+        if config.args.len() != function.inputs.len() {
+            return Err(eyre!(
+                "{} takes {} arguments, {} given",
+                function.name,
+                function.inputs.len(),
+                config.args.len()
+            ));
+        }
+        let mut args = Vec::new();
+        for (arg, input) in config.args.iter().zip(&function.inputs) {
+            if let Some(field) = arg.strip_prefix("log.") {
+                let event = event
+                    .as_ref()
+                    .ok_or_else(|| eyre!("{} needs an event from the ABI to read", arg))?;
+                if !event.inputs.iter().any(|param| param.name == field) {
+                    return Err(eyre!("{} has no field {}", event.name, field));
+                }
+                args.push(Arg::Log(field.to_string()));
+            } else {
+                let token = LenientTokenizer::tokenize(&input.kind, arg)
+                    .map_err(|_| eyre!("{:?} is not a {}", arg, input.kind))?;
+                args.push(Arg::Value(token));
+            }
+        }
+        if config.txs == 0 {
+            return Err(eyre!("txs must be at least 1"));
+        }
+        let gas = config.gas.unwrap_or(crate::SOLUTION_GAS);
+        if gas == 0 {
+            return Err(eyre!("gas must be at least 1"));
+        }
+        //the bundle is funded for every tx at once
+        if gas.checked_mul(config.txs).is_none() {
+            return Err(eyre!("gas {} times txs {} overflows", gas, config.txs));
+        }
+
+        Ok(Self {
+            flag: Flag::Rule(ctf::intern(&config.name)),
+            address: config.address,
+            topic0,
+            selector,
+            event,
+            to,
+            function,
+            args,
+            txs: config.txs,
+            gas,
+        })
+    }
+
+    /// Gas of all `txs` together, checked not to overflow when the rule was built.
+    pub fn total_gas(&self) -> u64 {
+        self.gas * self.txs
+    }
+
+    fn at_address(&self, address: Address) -> bool {
+        self.address.is_none() || self.address == Some(address)
+    }
+
+    fn reads_log(&self) -> bool {
+        self.args.iter().any(|arg| matches!(arg, Arg::Log(_)))
+    }
+
+    /// `Some` with the decoded log, if the rule names an ABI event, when `hint` matches.
+    fn matches(&self, hint: &mev_share::sse::Event) -> Option<Option<DecodedLog>> {
+        if let Some(address) = self.address {
+            let touched = hint.transactions.iter().any(|tx| tx.to == Some(address))
+                || hint.logs.iter().any(|log| log.address == address);
+            if !touched {
+                return None;
+            }
+        }
+        if let Some(selector) = self.selector {
+            //`to` may be hidden, the address check above already placed the hint
+            let called = hint.transactions.iter().any(|tx| {
+                let to_ours = match tx.to {
+                    Some(to) => self.at_address(to),
+                    None => true,
+                };
+                to_ours
+                    && tx
+                        .function_selector
+                        .as_ref()
+                        .is_some_and(|called| called.0 == selector)
+            });
+            if !called {
+                return None;
+            }
+        }
+        let Some(topic0) = self.topic0 else {
+            return Some(None);
+        };
+        let log = hint
+            .logs
+            .iter()
+            .find(|log| self.at_address(log.address) && log.topics.first() == Some(&topic0))?;
+        //hints can leave the data out, only a problem if an argument comes from it
+        let decoded = self.event.as_ref().and_then(|event| {
+            let log = Log {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+                ..Default::default()
+            };
+            decode_event(event, &log).ok()
+        });
+        if decoded.is_none() && self.reads_log() {
+            return None;
+        }
+        Some(decoded)
+    }
+
+    /// Calldata of the rule's call with arguments filled in from `log`.
+    pub fn calldata(&self, log: Option<&DecodedLog>) -> Result<Vec<u8>> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                Arg::Value(token) => Ok(token.clone()),
+                Arg::Log(field) => log
+                    .and_then(|log| log.param(field))
+                    .cloned()
+                    .ok_or_else(|| eyre!("matched log has no {}", field)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.function.encode_input(&args)?)
+    }
+}
+
+/// Config rules in order, the first to match a hint solves it.
+pub struct RuleEngine {
+    rules: Vec<Arc<Rule>>,
+}
+
+impl RuleEngine {
+    pub fn new(configs: &[RuleConfig], abis: &AbiRegistry) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (i, config) in configs.iter().enumerate() {
+            if configs[..i].iter().any(|other| other.name == config.name) {
+                return Err(eyre!("rules.{} is defined twice", config.name));
+            }
+            let rule =
+                Rule::build(config, abis).wrap_err_with(|| format!("rules.{}", config.name))?;
+            rules.push(Arc::new(rule));
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> &[Arc<Rule>] {
+        &self.rules
+    }
+
+    /// First rule matching `hint`, with the log its arguments come from.
+    pub fn matching(
+        &self,
+        hint: &mev_share::sse::Event,
+    ) -> Option<(Arc<Rule>, Option<DecodedLog>)> {
+        self.rules
+            .iter()
+            .find_map(|rule| rule.matches(hint).map(|log| (rule.clone(), log)))
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<[u8; 4]> {
+    let bytes = ethers::utils::hex::decode(selector.trim_start_matches("0x"))
+        .map_err(|_| eyre!("selector {:?} is not hex", selector))?;
+    bytes
+        .try_into()
+        .map_err(|_| eyre!("selector {:?} is not 4 bytes", selector))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ethers::abi::Abi;
+    use ethers::utils::hex;
+    use serde_json::json;
+
+    use super::*;
+
+    const MAGIC: &str = "mev_share_magic_number_v3";
+
+    fn abis() -> AbiRegistry {
+        AbiRegistry::load(None, &HashMap::new()).unwrap()
+    }
+
+    fn magic_abi(abis: &AbiRegistry) -> &Abi {
+        abis.abi(MAGIC).unwrap()
+    }
+
+    fn challenge() -> Address {
+        Address::repeat_byte(0xaa)
+    }
+
+    fn rule(toml: &str) -> Result<Rule> {
+        let config: RuleConfig = toml::from_str(toml).unwrap();
+        Rule::build(&config, &abis())
+    }
+
+    fn activate_topic() -> H256 {
+        magic_abi(&abis()).event("Activate").unwrap().signature()
+    }
+
+    fn activate_data(lower: u64, upper: u64) -> String {
+        format!(
+            "0x{}",
+            hex::encode(ethers::abi::encode(&[
+                Token::Uint(lower.into()),
+                Token::Uint(upper.into())
+            ]))
+        )
+    }
+
+    /// A hint as the event stream sends it.
+    fn hint(txs: serde_json::Value, logs: serde_json::Value) -> mev_share::sse::Event {
+        serde_json::from_value(json!({
+            "hash": format!("{:?}", H256::repeat_byte(1)),
+            "txs": txs,
+            "logs": logs,
+        }))
+        .unwrap()
+    }
+
+    fn activate_hint(address: Address, data: &str) -> mev_share::sse::Event {
+        hint(
+            json!([]),
+            json!([{
+                "address": format!("{:?}", address),
+                "topics": [format!("{:?}", activate_topic())],
+                "data": data,
+            }]),
+        )
+    }
+
+    fn claim_reward(number: u64) -> Vec<u8> {
+        magic_abi(&abis())
+            .function("claimReward")
+            .unwrap()
+            .encode_input(&[Token::Uint(number.into())])
+            .unwrap()
+    }
+
+    fn log_rule(event: &str) -> String {
+        format!(
+            "name = \"RuleLog\"\naddress = \"{:?}\"\nevent = \"{}\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"log.lowerBound\"]",
+            challenge(),
+            event,
+            MAGIC
+        )
+    }
+
+    #[test]
+    fn argument_from_matched_log() {
+        let rule = rule(&log_rule("Activate")).unwrap();
+        let log = rule
+            .matches(&activate_hint(challenge(), &activate_data(5, 10)))
+            .unwrap();
+        assert_eq!(rule.calldata(log.as_ref()).unwrap(), claim_reward(5));
+        assert_eq!(rule.to, challenge());
+        assert_eq!(rule.txs, 1);
+    }
+
+    #[test]
+    fn literal_arguments_are_tokenized_by_type() {
+        let number = rule(&format!(
+            "name = \"RuleLiteral\"\naddress = \"{:?}\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"42\"]",
+            challenge(),
+            MAGIC
+        ))
+        .unwrap();
+        assert_eq!(number.calldata(None).unwrap(), claim_reward(42));
+
+        let owner = Address::repeat_byte(0x11);
+        let address = rule(&format!(
+            "name = \"RuleAddress\"\naddress = \"{:?}\"\nabi = \"{}\"\ncall = \"transferOwnership\"\nargs = [\"{:?}\"]",
+            challenge(),
+            MAGIC,
+            owner
+        ))
+        .unwrap();
+        let expected = magic_abi(&abis())
+            .function("transferOwnership")
+            .unwrap()
+            .encode_input(&[Token::Address(owner)])
+            .unwrap();
+        assert_eq!(address.calldata(None).unwrap(), expected);
+    }
+
+    #[test]
+    fn bad_arguments_are_rejected_at_build() {
+        let base = |args: &str| {
+            format!(
+                "name = \"RuleBad\"\naddress = \"{:?}\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = {}",
+                challenge(),
+                MAGIC,
+                args
+            )
+        };
+        //not a uint
+        assert!(rule(&base("[\"lots\"]")).is_err());
+        //wrong count
+        assert!(rule(&base("[\"1\", \"2\"]")).is_err());
+        //no event to read from
+        assert!(rule(&base("[\"log.lowerBound\"]")).is_err());
+    }
+
+    #[test]
+    fn build_needs_something_to_match_and_a_free_name() {
+        let call = format!(
+            "abi = \"{}\"\ncall = \"claimReward\"\nargs = [\"1\"]",
+            MAGIC
+        );
+        assert!(rule(&format!("name = \"RuleNothing\"\n{}", call)).is_err());
+        assert!(rule(&format!(
+            "name = \"CTFSimple\"\nselector = \"0x12345678\"\n{}",
+            call
+        ))
+        .is_err());
+        assert!(rule(&format!(
+            "name = \"RuleNoTxs\"\nselector = \"0x12345678\"\ntxs = 0\n{}",
+            call
+        ))
+        .is_err());
+        assert!(rule(&format!(
+            "name = \"RuleField\"\nevent = \"Activate\"\n{}",
+            call.replace("[\"1\"]", "[\"log.middleBound\"]")
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn build_refuses_zero_gas() {
+        let config = format!(
+            "name = \"RuleNoGas\"\nselector = \"0x12345678\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"1\"]",
+            MAGIC
+        );
+        assert!(rule(&format!("{}\ngas = 0", config)).is_err());
+        assert_eq!(rule(&format!("{}\ngas = 1", config)).unwrap().gas, 1);
+        assert_eq!(rule(&config).unwrap().gas, crate::SOLUTION_GAS);
+    }
+
+    #[test]
+    fn build_refuses_overflowing_total_gas() {
+        let config = format!(
+            "name = \"RuleLotsOfGas\"\nselector = \"0x12345678\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"1\"]",
+            MAGIC
+        );
+        //toml integers stop at i64::MAX
+        let gas = i64::MAX as u64;
+        assert!(rule(&format!("{}\ngas = {}\ntxs = 3", config, gas)).is_err());
+        let built = rule(&format!("{}\ngas = {}\ntxs = 2", config, gas)).unwrap();
+        assert_eq!(built.total_gas(), u64::MAX - 1);
+    }
+
+    #[test]
+    fn event_by_name_or_topic0() {
+        let by_topic = rule(&log_rule(&format!("{:?}", activate_topic()))).unwrap();
+        let by_name = rule(&log_rule("Activate")).unwrap();
+        let hint = activate_hint(challenge(), &activate_data(7, 9));
+        for rule in [by_topic, by_name] {
+            let log = rule.matches(&hint).unwrap();
+            assert_eq!(rule.calldata(log.as_ref()).unwrap(), claim_reward(7));
+        }
+    }
+
+    #[test]
+    fn log_has_to_be_at_the_address_with_the_topic0() {
+        let rule = rule(&log_rule("Activate")).unwrap();
+        let elsewhere = activate_hint(Address::repeat_byte(0xbb), &activate_data(5, 10));
+        assert!(rule.matches(&elsewhere).is_none());
+
+        let other_topic = hint(
+            json!([{ "to": format!("{:?}", challenge()) }]),
+            json!([{
+                "address": format!("{:?}", challenge()),
+                "topics": [format!("{:?}", H256::repeat_byte(2))],
+                "data": activate_data(5, 10),
+            }]),
+        );
+        assert!(rule.matches(&other_topic).is_none());
+    }
+
+    #[test]
+    fn selector_matching() {
+        let rule = rule(&format!(
+            "name = \"RuleSelector\"\naddress = \"{:?}\"\nselector = \"0x12345678\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"1\"]",
+            challenge(),
+            MAGIC
+        ))
+        .unwrap();
+        let call = |to: Option<Address>, selector: &str| {
+            hint(
+                json!([{
+                    "to": to.map(|to| format!("{:?}", to)),
+                    "functionSelector": selector,
+                }]),
+                json!([{
+                    "address": format!("{:?}", challenge()),
+                    "topics": [],
+                    "data": "0x",
+                }]),
+            )
+        };
+        assert!(rule
+            .matches(&call(Some(challenge()), "0x12345678"))
+            .is_some());
+        assert!(rule
+            .matches(&call(Some(challenge()), "0x87654321"))
+            .is_none());
+        //a hidden to is placed by the log at the address
+        assert!(rule.matches(&call(None, "0x12345678")).is_some());
+        assert!(rule
+            .matches(&call(Some(Address::repeat_byte(0xbb)), "0x12345678"))
+            .is_none());
+    }
+
+    #[test]
+    fn missing_log_data() {
+        let hidden = activate_hint(challenge(), "0x");
+        //an argument from the log needs the data
+        let reads_log = rule(&log_rule("Activate")).unwrap();
+        assert!(reads_log.matches(&hidden).is_none());
+
+        //a literal call only needs the topic0
+        let literal = rule(&format!(
+            "name = \"RuleHidden\"\naddress = \"{:?}\"\nevent = \"Activate\"\nabi = \"{}\"\ncall = \"claimReward\"\nargs = [\"3\"]",
+            challenge(),
+            MAGIC
+        ))
+        .unwrap();
+        let log = literal.matches(&hidden).unwrap();
+        assert!(log.is_none());
+        assert_eq!(literal.calldata(log.as_ref()).unwrap(), claim_reward(3));
+        //and a log read against no log fails rather than encoding garbage
+        assert!(reads_log.calldata(None).is_err());
+    }
+}
@@ -0,0 +1,615 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use ethers::abi::token::{LenientTokenizer, Tokenizer};
+use ethers::abi::Token;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::{get_contract_address, get_create2_address, hex, keccak256};
+use eyre::{eyre, Result, WrapErr};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ImmutableString, Map, Scope, AST, INT};
+use tokio::runtime::Handle;
+use tracing::{debug, info, warn};
+
+use crate::bundle::Hint;
+use crate::ctf::{self, Flag};
+use crate::metrics;
+use crate::registry::AbiRegistry;
+
+//how often the scripts dir is checked for added, changed or removed scripts
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+//per call, keeps a runaway loop from holding a blocking thread
+const MAX_OPERATIONS: u64 = 1_000_000;
+//for every script's `matches` on one hint together, chain reads included
+const MATCH_TIMEOUT: Duration = Duration::from_secs(1);
+//per chain read, a hung node fails the script rather than holding its blocking thread
+const RPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A call a script wants made, signed and sent in the backrun bundle.
+#[derive(Debug, Clone)]
+pub struct ScriptCall {
+    pub to: Address,
+    pub data: Vec<u8>,
+    pub gas: u64,
+}
+
+/// What a script's `solve` asks for. Each stage is nested in the bundle of the next so it
+/// only lands on top of the earlier ones, all of it shares `share` and may land in `blocks` blocks.
+#[derive(Debug, Clone)]
+pub struct ScriptSolution {
+    pub stages: Vec<Vec<ScriptCall>>,
+    pub share: Option<Vec<Hint>>,
+    pub blocks: u64,
+}
+
+impl ScriptSolution {
+    pub fn calls(&self) -> impl Iterator<Item = &ScriptCall> {
+        self.stages.iter().flatten()
+    }
+}
+
+/// One compiled `*.rhai` file, named after its file stem.
+pub struct Script {
+    pub flag: Flag,
+    ast: AST,
+}
+
+/// Solvers written as Rhai scripts in a directory, reloaded whenever a file changes.
+/// Each script defines `matches(hint)`, run on every hint no other solver took, and
+/// `solve(hint)` returning the `#{to, data, gas}` calls to backrun it with, or
+/// `#{stages, share, blocks}` for a chained bundle.
+pub struct ScriptSolvers {
+    dir: PathBuf,
+    engine: Engine,
+    abis: Arc<AbiRegistry>,
+    scripts: RwLock<BTreeMap<PathBuf, Arc<Script>>>,
+    //modified time of each file as of the last load attempt, good or not
+    versions: Mutex<HashMap<PathBuf, SystemTime>>,
+}
+
+impl ScriptSolvers {
+    /// Load every script in `dir`, then keep checking it for changes.
+    pub fn spawn(dir: PathBuf, client: Arc<Provider<Ws>>, abis: Arc<AbiRegistry>) -> Arc<Self> {
+        let solvers = Arc::new(Self {
+            dir,
+            engine: engine(client, abis.clone()),
+            abis,
+            scripts: RwLock::new(BTreeMap::new()),
+            versions: Mutex::new(HashMap::new()),
+        });
+        solvers.reload();
+
+        let reloader = solvers.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            loop {
+                interval.tick().await;
+                reloader.reload();
+            }
+        });
+        solvers
+    }
+
+    fn reload(&self) {
+        let paths = match script_paths(&self.dir) {
+            Ok(paths) => paths,
+            Err(e) => {
+                warn!(dir = ?self.dir, "Failed to list scripts {:?}", e);
+                return;
+            }
+        };
+        for path in &paths {
+            let Ok(modified) = fs::metadata(path).and_then(|meta| meta.modified()) else {
+                continue;
+            };
+            let previous = self.versions.lock().unwrap().insert(path.clone(), modified);
+            if previous == Some(modified) {
+                continue;
+            }
+            //a broken edit leaves the last good version running
+            match self.compile(path) {
+                Ok(script) => {
+                    info!(script = script.flag.name(), "Loaded script solver");
+                    self.scripts
+                        .write()
+                        .unwrap()
+                        .insert(path.clone(), Arc::new(script));
+                }
+                Err(e) => warn!(path = ?path, "Failed to load script {:?}", e),
+            }
+        }
+        self.versions
+            .lock()
+            .unwrap()
+            .retain(|path, _| paths.contains(path));
+        self.scripts.write().unwrap().retain(|path, script| {
+            let kept = paths.contains(path);
+            if !kept {
+                info!(script = script.flag.name(), "Script removed");
+            }
+            kept
+        });
+    }
+
+    fn compile(&self, path: &Path) -> Result<Script> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| eyre!("script file name is not utf-8"))?;
+        if ctf::contracts().values().any(|flag| flag.name() == name) {
+            return Err(eyre!("{} is taken by a built in challenge", name));
+        }
+        let source = fs::read_to_string(path)?;
+        let ast = self.engine.compile(source).map_err(|e| eyre!("{}", e))?;
+        for function in ["matches", "solve"] {
+            if !ast
+                .iter_functions()
+                .any(|f| f.name == function && f.params.len() == 1)
+            {
+                return Err(eyre!("no {}(hint) function", function));
+            }
+        }
+        Ok(Script {
+            flag: Flag::Script(ctf::intern(name)),
+            ast,
+        })
+    }
+
+    /// First script, by file name, whose `matches` takes `hint`. Runs on the blocking pool
+    /// and is given up on after `MATCH_TIMEOUT`, so a slow script costs only its own hint.
+    pub async fn matching(self: &Arc<Self>, hint: mev_share::sse::Event) -> Option<Arc<Script>> {
+        let scripts: Vec<Arc<Script>> = self.scripts.read().unwrap().values().cloned().collect();
+        if scripts.is_empty() {
+            return None;
+        }
+        let solvers = self.clone();
+        let matching = tokio::task::spawn_blocking(move || {
+            let hint = hint_value(&hint, &solvers.abis);
+            scripts.into_iter().find(|script| {
+                match solvers.engine.call_fn::<bool>(
+                    &mut Scope::new(),
+                    &script.ast,
+                    "matches",
+                    (hint.clone(),),
+                ) {
+                    Ok(matched) => matched,
+                    Err(e) => {
+                        warn!(script = script.flag.name(), "Script matches failed {}", e);
+                        false
+                    }
+                }
+            })
+        });
+        match tokio::time::timeout(MATCH_TIMEOUT, matching).await {
+            Ok(Ok(script)) => script,
+            Ok(Err(e)) => {
+                warn!("Script matching panicked {:?}", e);
+                None
+            }
+            Err(_) => {
+                warn!("Script matching timed out");
+                None
+            }
+        }
+    }
+
+    /// Run `script`'s `solve` on the blocking pool since its chain reads block.
+    pub async fn solve(
+        self: &Arc<Self>,
+        script: Arc<Script>,
+        hint: mev_share::sse::Event,
+    ) -> Result<ScriptSolution> {
+        let solvers = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let hint = hint_value(&hint, &solvers.abis);
+            let solution: Dynamic = solvers
+                .engine
+                .call_fn(&mut Scope::new(), &script.ast, "solve", (hint,))
+                .map_err(|e| eyre!("{} solve failed: {}", script.flag.name(), e))?;
+            script_solution(solution)
+        })
+        .await?
+    }
+}
+
+/// Script names in `dir`, for checking per challenge config tables.
+pub fn script_names(dir: &Path) -> Vec<String> {
+    script_paths(dir)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+        .collect()
+}
+
+fn script_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).wrap_err_with(|| format!("reading scripts dir {:?}", dir))? {
+        let path = entry?.path();
+        if path.extension() == Some(OsStr::new("rhai")) {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// The sandbox: no imports, eval or file access, bounded work, and only the functions below.
+fn engine(client: Arc<Provider<Ws>>, abis: Arc<AbiRegistry>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(10_000);
+    engine.on_print(|text| info!(output = text, "Script print"));
+    engine.on_debug(
+        |text, source, position| debug!(output = text, source = ?source, %position, "Script debug"),
+    );
+
+    //abi and address helpers
+    engine.register_fn(
+        "encode",
+        move |abi: ImmutableString, function: ImmutableString, args: Array| {
+            script_result(encode(&abis, &abi, &function, &args))
+        },
+    );
+    engine.register_fn("keccak256", |data: ImmutableString| {
+        script_result(decode_hex(&data).map(|data| to_hex(&keccak256(data))))
+    });
+    engine.register_fn("create_address", |deployer: ImmutableString, nonce: INT| {
+        script_result(
+            parse_address(&deployer)
+                .map(|deployer| format!("{:?}", get_contract_address(deployer, nonce))),
+        )
+    });
+    engine.register_fn(
+        "create2_address",
+        |deployer: ImmutableString, salt: ImmutableString, init_code: ImmutableString| {
+            script_result(create2_address(&deployer, &salt, &init_code))
+        },
+    );
+
+    //chain reads at the latest block
+    let rpc = client.clone();
+    engine.register_fn("block_number", move || {
+        script_result(
+            block_on(metrics::time_rpc(
+                "get_block_number",
+                rpc.get_block_number(),
+            ))
+            .map(|block| block.as_u64() as INT),
+        )
+    });
+    let rpc = client.clone();
+    engine.register_fn("balance", move |address: ImmutableString| {
+        script_result(balance(&rpc, &address))
+    });
+    let rpc = client.clone();
+    engine.register_fn("code", move |address: ImmutableString| {
+        script_result(code(&rpc, &address))
+    });
+    let rpc = client.clone();
+    engine.register_fn(
+        "storage",
+        move |address: ImmutableString, slot: ImmutableString| {
+            script_result(storage(&rpc, &address, &slot))
+        },
+    );
+    let rpc = client;
+    engine.register_fn(
+        "eth_call",
+        move |to: ImmutableString, data: ImmutableString| script_result(eth_call(&rpc, &to, &data)),
+    );
+    engine
+}
+
+//scripts are sync and only run on the blocking pool, chain reads block on the bot's runtime.
+//giving up on the script's wait does not stop it, so every read has its own deadline
+fn block_on<T, E>(fut: impl Future<Output = Result<T, E>>) -> Result<T>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Handle::current()
+        .block_on(tokio::time::timeout(RPC_TIMEOUT, fut))
+        .map_err(|_| eyre!("chain read timed out after {:?}", RPC_TIMEOUT))?
+        .map_err(Into::into)
+}
+
+fn create2_address(deployer: &str, salt: &str, init_code: &str) -> Result<String> {
+    let salt: H256 = salt.parse().map_err(|_| eyre!("{} is not a salt", salt))?;
+    let address = get_create2_address(parse_address(deployer)?, salt, decode_hex(init_code)?);
+    Ok(format!("{:?}", address))
+}
+
+fn balance(rpc: &Provider<Ws>, address: &str) -> Result<String> {
+    let balance = block_on(metrics::time_rpc(
+        "get_balance",
+        rpc.get_balance(parse_address(address)?, None),
+    ))?;
+    Ok(balance.to_string())
+}
+
+fn code(rpc: &Provider<Ws>, address: &str) -> Result<String> {
+    let code = block_on(metrics::time_rpc(
+        "get_code",
+        rpc.get_code(parse_address(address)?, None),
+    ))?;
+    Ok(to_hex(&code))
+}
+
+fn storage(rpc: &Provider<Ws>, address: &str, slot: &str) -> Result<String> {
+    let slot = H256::from_uint(&parse_uint(slot)?);
+    let value = block_on(metrics::time_rpc(
+        "get_storage_at",
+        rpc.get_storage_at(parse_address(address)?, slot, None),
+    ))?;
+    Ok(to_hex(value.as_bytes()))
+}
+
+fn eth_call(rpc: &Provider<Ws>, to: &str, data: &str) -> Result<String> {
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(parse_address(to)?)
+        .data(decode_hex(data)?)
+        .into();
+    let output = block_on(metrics::time_rpc("call", rpc.call(&tx, None)))?;
+    Ok(to_hex(&output))
+}
+
+fn script_result<T>(result: Result<T>) -> Result<T, Box<EvalAltResult>> {
+    result.map_err(|e| format!("{:#}", e).into())
+}
+
+/// Calldata for `function` of ABI `abi`, arguments tokenized by the parameter types.
+fn encode(abis: &AbiRegistry, abi: &str, function: &str, args: &Array) -> Result<String> {
+    let function = abis.abi(abi)?.function(function)?;
+    if args.len() != function.inputs.len() {
+        return Err(eyre!(
+            "{} takes {} arguments, {} given",
+            function.name,
+            function.inputs.len(),
+            args.len()
+        ));
+    }
+    let tokens = args
+        .iter()
+        .zip(&function.inputs)
+        .map(|(arg, input)| {
+            let arg = arg_string(arg);
+            LenientTokenizer::tokenize(&input.kind, &arg)
+                .map_err(|_| eyre!("{:?} is not a {}", arg, input.kind))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(to_hex(&function.encode_input(&tokens)?))
+}
+
+//arrays in the form the tokenizer reads them
+fn arg_string(arg: &Dynamic) -> String {
+    match arg.clone().try_cast::<Array>() {
+        Some(items) => format!(
+            "[{}]",
+            items.iter().map(arg_string).collect::<Vec<_>>().join(",")
+        ),
+        None => arg.to_string(),
+    }
+}
+
+/// The hint as a script sees it, logs of registered challenges decoded through their ABI.
+fn hint_value(hint: &mev_share::sse::Event, abis: &AbiRegistry) -> Dynamic {
+    let txs: Array = hint
+        .transactions
+        .iter()
+        .map(|tx| {
+            let mut map = Map::new();
+            map.insert("to".into(), optional(tx.to.map(|to| format!("{:?}", to))));
+            map.insert(
+                "selector".into(),
+                optional(
+                    tx.function_selector
+                        .as_ref()
+                        .map(|selector| to_hex(&selector.0)),
+                ),
+            );
+            map.insert(
+                "calldata".into(),
+                optional(tx.calldata.as_ref().map(|calldata| to_hex(calldata))),
+            );
+            Dynamic::from_map(map)
+        })
+        .collect();
+    let logs: Array = hint
+        .logs
+        .iter()
+        .map(|log| {
+            let log = Log {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+                ..Default::default()
+            };
+            let mut map = Map::new();
+            map.insert("address".into(), format!("{:?}", log.address).into());
+            map.insert(
+                "topics".into(),
+                Dynamic::from_array(
+                    log.topics
+                        .iter()
+                        .map(|topic| format!("{:?}", topic).into())
+                        .collect(),
+                ),
+            );
+            map.insert("data".into(), to_hex(&log.data).into());
+            let decoded = abis.decode_log(&log).ok().flatten();
+            map.insert(
+                "event".into(),
+                optional(decoded.as_ref().map(|decoded| decoded.event.clone())),
+            );
+            let params: Map = decoded
+                .map(|decoded| {
+                    decoded
+                        .params
+                        .iter()
+                        .map(|(name, value)| (name.as_str().into(), token_value(value)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            map.insert("params".into(), Dynamic::from_map(params));
+            Dynamic::from_map(map)
+        })
+        .collect();
+    let mut map = Map::new();
+    map.insert("hash".into(), format!("{:?}", hint.hash).into());
+    map.insert("txs".into(), Dynamic::from_array(txs));
+    map.insert("logs".into(), Dynamic::from_array(logs));
+    Dynamic::from_map(map)
+}
+
+/// Numbers that fit are script ints, bigger ones decimal strings, bytes and addresses hex.
+fn token_value(token: &Token) -> Dynamic {
+    match token {
+        Token::Address(address) => format!("{:?}", address).into(),
+        Token::Uint(value) if *value <= U256::from(INT::MAX) => (value.as_u64() as INT).into(),
+        Token::Uint(value) => value.to_string().into(),
+        Token::Int(value) => {
+            let value = I256::from_raw(*value);
+            if value >= I256::from(INT::MIN) && value <= I256::from(INT::MAX) {
+                value.as_i64().into()
+            } else {
+                value.to_string().into()
+            }
+        }
+        Token::Bool(value) => (*value).into(),
+        Token::String(value) => value.clone().into(),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => to_hex(bytes).into(),
+        Token::Array(items) | Token::FixedArray(items) | Token::Tuple(items) => {
+            Dynamic::from_array(items.iter().map(token_value).collect())
+        }
+    }
+}
+
+/// What `solve` returned, calls as `script_calls` reads them or
+/// `#{stages: [[calls], ..], share: ["logs", ..], blocks: n}`.
+fn script_solution(value: Dynamic) -> Result<ScriptSolution> {
+    let Some(map) = value
+        .clone()
+        .try_cast::<Map>()
+        .filter(|map| map.contains_key("stages"))
+    else {
+        let calls = script_calls(value)?;
+        return Ok(ScriptSolution {
+            stages: if calls.is_empty() {
+                Vec::new()
+            } else {
+                vec![calls]
+            },
+            share: None,
+            blocks: 1,
+        });
+    };
+    let stages = map["stages"]
+        .clone()
+        .try_cast::<Array>()
+        .ok_or_else(|| eyre!("stages has to be an array of call arrays"))?
+        .into_iter()
+        .map(script_calls)
+        .collect::<Result<Vec<_>>>()?;
+    if stages.iter().any(|stage| stage.is_empty()) {
+        return Err(eyre!("solve returned an empty stage"));
+    }
+    let share = match map.get("share") {
+        Some(hints) => Some(
+            hints
+                .clone()
+                .try_cast::<Array>()
+                .ok_or_else(|| eyre!("share has to be an array of hint names"))?
+                .iter()
+                .map(|hint| hint.to_string().parse())
+                .collect::<Result<Vec<Hint>>>()?,
+        ),
+        None => None,
+    };
+    let blocks = match map.get("blocks") {
+        Some(blocks) => blocks
+            .as_int()
+            .ok()
+            .and_then(|blocks| u64::try_from(blocks).ok())
+            .filter(|blocks| *blocks > 0)
+            .ok_or_else(|| eyre!("blocks has to be a positive int"))?,
+        None => 1,
+    };
+    Ok(ScriptSolution {
+        stages,
+        share,
+        blocks,
+    })
+}
+
+/// Calls of one stage, nothing, one call map or an array of them.
+fn script_calls(value: Dynamic) -> Result<Vec<ScriptCall>> {
+    if value.is_unit() {
+        return Ok(Vec::new());
+    }
+    let items = match value.clone().try_cast::<Array>() {
+        Some(items) => items,
+        None => vec![value],
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            let call = item
+                .try_cast::<Map>()
+                .ok_or_else(|| eyre!("solve has to return #{{to, data}} maps"))?;
+            let field = |name: &str| {
+                call.get(name)
+                    .map(|value| value.to_string())
+                    .ok_or_else(|| eyre!("call has no {}", name))
+            };
+            let gas = match call.get("gas") {
+                Some(gas) => gas
+                    .as_int()
+                    .ok()
+                    .and_then(|gas| u64::try_from(gas).ok())
+                    .filter(|gas| *gas > 0)
+                    .ok_or_else(|| eyre!("call gas has to be a positive int"))?,
+                None => crate::SOLUTION_GAS,
+            };
+            Ok(ScriptCall {
+                to: parse_address(&field("to")?)?,
+                data: decode_hex(&field("data")?)?,
+                gas,
+            })
+        })
+        .collect()
+}
+
+fn optional(value: Option<String>) -> Dynamic {
+    value.map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>> {
+    hex::decode(data.trim_start_matches("0x")).map_err(|_| eyre!("{:?} is not hex", data))
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    address
+        .parse()
+        .map_err(|_| eyre!("{:?} is not an address", address))
+}
+
+fn parse_uint(value: &str) -> Result<U256> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16),
+        None => U256::from_dec_str(value),
+    }
+    .map_err(|_| eyre!("{:?} is not a number", value))
+}
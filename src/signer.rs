@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::rlp::Rlp;
+use eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::config::Secret;
+
+/// Where a key lives, used for both the bot and the flashbots identity.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+    /// hex private key straight from config/env
+    PrivateKey { key: Secret },
+    /// file holding a hex private key, must not be readable by group/others
+    KeyFile { path: PathBuf },
+    /// encrypted json keystore
+    Keystore {
+        path: PathBuf,
+        passphrase: Option<Secret>,
+        passphrase_file: Option<PathBuf>,
+    },
+    /// web3signer-compatible json-rpc signer holding `address`
+    Remote { url: String, address: Address },
+}
+
+impl SignerConfig {
+    pub async fn build(&self, chain_id: u64) -> Result<BotSigner> {
+        let signer = match self {
+            SignerConfig::PrivateKey { key } => BotSigner::Local(parse_key(key.expose())?),
+            SignerConfig::KeyFile { path } => {
+                check_permissions(path)?;
+                let key =
+                    fs::read_to_string(path).wrap_err_with(|| format!("reading {:?}", path))?;
+                BotSigner::Local(parse_key(&key)?)
+            }
+            SignerConfig::Keystore {
+                path,
+                passphrase,
+                passphrase_file,
+            } => {
+                let passphrase = match (passphrase, passphrase_file) {
+                    (Some(passphrase), _) => passphrase.expose().to_owned(),
+                    (None, Some(file)) => {
+                        check_permissions(file)?;
+                        fs::read_to_string(file)?.trim_end().to_owned()
+                    }
+                    (None, None) => return Err(eyre!("keystore {:?} has no passphrase", path)),
+                };
+                let wallet = LocalWallet::decrypt_keystore(path, passphrase)
+                    .map_err(|_| eyre!("failed to decrypt keystore {:?}", path))?;
+                BotSigner::Local(wallet)
+            }
+            SignerConfig::Remote { url, address } => {
+                BotSigner::Remote(RemoteSigner::connect(url, *address).await?)
+            }
+        };
+        Ok(signer.with_chain_id(chain_id))
+    }
+
+    /// Offline checks for `Config::validate`, never echoes key material.
+    pub fn problems(&self, name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        match self {
+            SignerConfig::PrivateKey { key } => {
+                if parse_key(key.expose()).is_err() {
+                    problems.push(format!("{} is not a valid hex private key", name));
+                }
+            }
+            SignerConfig::KeyFile { path } => {
+                if let Err(e) = check_permissions(path) {
+                    problems.push(format!("{}: {}", name, e));
+                }
+            }
+            SignerConfig::Keystore {
+                path,
+                passphrase,
+                passphrase_file,
+            } => {
+                if !path.exists() {
+                    problems.push(format!("{} keystore {:?} does not exist", name, path));
+                }
+                if passphrase.is_none() && passphrase_file.is_none() {
+                    problems.push(format!("{} keystore has no passphrase", name));
+                }
+            }
+            SignerConfig::Remote { url, .. } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    problems.push(format!("{} remote signer must be http/https", name));
+                }
+            }
+        }
+        problems
+    }
+}
+
+/// Signs bot transactions and flashbots requests whatever the key backend.
+#[derive(Debug, Clone)]
+pub enum BotSigner {
+    Local(LocalWallet),
+    Remote(RemoteSigner),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+    #[error("remote signer: {0}")]
+    Remote(#[from] ProviderError),
+    #[error("remote signer returned a malformed signature")]
+    MalformedSignature,
+    #[error("remote signer does not support {0}")]
+    Unsupported(&'static str),
+}
+
+#[async_trait]
+impl Signer for BotSigner {
+    type Error = SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            BotSigner::Local(wallet) => Ok(wallet.sign_message(message).await?),
+            BotSigner::Remote(remote) => remote.sign_message(message.as_ref()).await,
+        }
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        match self {
+            BotSigner::Local(wallet) => Ok(wallet.sign_transaction(tx).await?),
+            BotSigner::Remote(remote) => remote.sign_transaction(tx).await,
+        }
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            BotSigner::Local(wallet) => Ok(wallet.sign_typed_data(payload).await?),
+            BotSigner::Remote(_) => Err(SignerError::Unsupported("typed data")),
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            BotSigner::Local(wallet) => wallet.address(),
+            BotSigner::Remote(remote) => remote.address,
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            BotSigner::Local(wallet) => wallet.chain_id(),
+            BotSigner::Remote(remote) => remote.chain_id,
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            BotSigner::Local(wallet) => BotSigner::Local(wallet.with_chain_id(chain_id)),
+            BotSigner::Remote(remote) => BotSigner::Remote(RemoteSigner {
+                chain_id: chain_id.into(),
+                ..remote
+            }),
+        }
+    }
+}
+
+/// Keys held by an external signer speaking the eth_sign/eth_signTransaction json-rpc.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    provider: Provider<Http>,
+    address: Address,
+    chain_id: u64,
+}
+
+impl RemoteSigner {
+    /// Fails unless the signer actually manages `address`.
+    pub async fn connect(url: &str, address: Address) -> Result<Self> {
+        let provider = Provider::<Http>::try_from(url)?;
+        let accounts: Vec<Address> = provider
+            .request("eth_accounts", ())
+            .await
+            .wrap_err("remote signer eth_accounts")?;
+        if !accounts.contains(&address) {
+            return Err(eyre!("remote signer does not hold {:?}", address));
+        }
+        Ok(Self {
+            provider,
+            address,
+            chain_id: 1,
+        })
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let signature: Bytes = self
+            .provider
+            .request("eth_sign", (self.address, Bytes::from(message.to_vec())))
+            .await?;
+        Signature::try_from(signature.as_ref()).map_err(|_| SignerError::MalformedSignature)
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, SignerError> {
+        let mut tx = tx.clone();
+        tx.set_from(self.address);
+        tx.set_chain_id(self.chain_id);
+        let signed: Bytes = self.provider.request("eth_signTransaction", [&tx]).await?;
+        //the signer hands back the whole raw tx, we only want its signature
+        let (_, signature) = TypedTransaction::decode_signed(&Rlp::new(&signed))
+            .map_err(|_| SignerError::MalformedSignature)?;
+        Ok(signature)
+    }
+}
+
+fn parse_key(key: &str) -> Result<LocalWallet> {
+    //never include the parse error, it can contain the key
+    key.trim()
+        .parse::<LocalWallet>()
+        .map_err(|_| eyre!("not a valid hex private key"))
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path)
+        .wrap_err_with(|| format!("reading {:?}", path))?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        return Err(eyre!(
+            "{:?} is accessible by group/others (mode {:o}), chmod 600 it",
+            path,
+            mode & 0o777
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(path: &Path) -> Result<()> {
+    fs::metadata(path).wrap_err_with(|| format!("reading {:?}", path))?;
+    Ok(())
+}
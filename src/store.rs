@@ -0,0 +1,379 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::{keccak256, rlp::Rlp};
+use eyre::{eyre, Result};
+use jsonrpsee::core::Error as RpcError;
+use mev_share::rpc::{SendBundleRequest, SendBundleResponse};
+use mev_share::sse::Event;
+use rusqlite::{params, Connection};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::bundle;
+use crate::captures::Capture;
+use crate::ctf::Flag;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    hash TEXT PRIMARY KEY,
+    received_at INTEGER NOT NULL,
+    contract TEXT,
+    challenge TEXT,
+    raw TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bundles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_hash TEXT,
+    challenge TEXT NOT NULL,
+    target_block INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    raw TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS bundle_txs (
+    bundle_id INTEGER NOT NULL REFERENCES bundles(id),
+    idx INTEGER NOT NULL,
+    hash TEXT NOT NULL,
+    sender TEXT,
+    nonce INTEGER,
+    raw TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS relay_responses (
+    bundle_id INTEGER NOT NULL REFERENCES bundles(id),
+    relay TEXT NOT NULL,
+    ok INTEGER NOT NULL,
+    response TEXT NOT NULL,
+    received_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS captures (
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    contract TEXT NOT NULL,
+    kind TEXT,
+    challenge TEXT NOT NULL,
+    searcher TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    PRIMARY KEY (tx_hash, log_index)
+);
+CREATE INDEX IF NOT EXISTS bundles_challenge ON bundles(challenge, created_at);
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Queued,
+    /// target block passed before it reached a relay
+    Dropped,
+    /// accepted by at least one relay
+    Sent,
+    /// every relay returned an error
+    Rejected,
+    /// sent and our first tx in it landed within its blocks
+    Included,
+    /// sent but its blocks passed without our first tx in them
+    NotIncluded,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Queued => "queued",
+            Outcome::Dropped => "dropped",
+            Outcome::Sent => "sent",
+            Outcome::Rejected => "rejected",
+            Outcome::Included => "included",
+            Outcome::NotIncluded => "not_included",
+        }
+    }
+}
+
+/// A bundle we built, as listed by the `attempts` command.
+#[derive(Debug)]
+pub struct Attempt {
+    pub id: i64,
+    pub challenge: String,
+    pub event_hash: Option<String>,
+    pub target_block: u64,
+    pub txs: u64,
+    pub outcome: String,
+    pub created_at: u64,
+}
+
+/// A flag the capture logger credited us with, as listed by the `captures` command.
+#[derive(Debug)]
+pub struct CaptureRecord {
+    pub contract: String,
+    /// `DeployKind` name, only for `MevShareNewContracts`
+    pub kind: Option<String>,
+    pub challenge: String,
+    pub searcher: String,
+    pub block: u64,
+    pub tx_hash: String,
+}
+
+type Write = Box<dyn FnOnce(&mut Connection) -> rusqlite::Result<()> + Send>;
+
+/// Local sqlite record of every event, bundle and relay response.
+/// Writes are queued to a writer thread so the event loop and submissions never wait on disk,
+/// reads (the cli commands) go through their own connection.
+pub struct Store {
+    conn: Mutex<Connection>,
+    writes: mpsc::UnboundedSender<Write>,
+    //ids are handed out here so recording a bundle does not wait for its insert
+    last_bundle_id: AtomicI64,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let writer = Connection::open(path)?;
+        //WAL commits without an fsync each and lets reads run alongside writes
+        writer
+            .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        writer.pragma_update(None, "synchronous", "NORMAL")?;
+        writer.execute_batch(SCHEMA)?;
+        //captures.kind came later, stores from before lack it
+        if writer.prepare("SELECT kind FROM captures LIMIT 0").is_err() {
+            writer.execute_batch("ALTER TABLE captures ADD COLUMN kind TEXT")?;
+        }
+        let last_bundle_id =
+            writer.query_row("SELECT COALESCE(MAX(id), 0) FROM bundles", [], |row| {
+                row.get(0)
+            })?;
+        let (writes, queued) = mpsc::unbounded_channel();
+        thread::Builder::new()
+            .name("store-writer".into())
+            .spawn(move || write_queued(writer, queued))?;
+        Ok(Self {
+            conn: Mutex::new(Connection::open(path)?),
+            writes,
+            last_bundle_id: AtomicI64::new(last_bundle_id),
+        })
+    }
+
+    fn write(
+        &self,
+        write: impl FnOnce(&mut Connection) -> rusqlite::Result<()> + Send + 'static,
+    ) -> Result<()> {
+        self.writes
+            .send(Box::new(write))
+            .map_err(|_| eyre!("store writer stopped"))
+    }
+
+    pub fn record_event(
+        &self,
+        event: &Event,
+        contract: Option<Address>,
+        flag: Option<&Flag>,
+    ) -> Result<()> {
+        let hash = format!("{:?}", event.hash);
+        let received_at = now();
+        let contract = contract.map(|c| format!("{:?}", c));
+        let challenge = flag.map(|f| f.name());
+        let raw = serde_json::to_string(event)?;
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO events (hash, received_at, contract, challenge, raw)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![hash, received_at, contract, challenge, raw],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record a freshly built bundle and its txs, returns the id outcomes are recorded against.
+    pub fn record_bundle(&self, flag: &Flag, bundle: &SendBundleRequest) -> Result<i64> {
+        let bundle_id = self.last_bundle_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let event_hash = bundle::target(bundle).map(|hash| format!("{:?}", hash));
+        let challenge = flag.name();
+        let target_block = bundle.inclusion.block.as_u64();
+        let created_at = now();
+        let raw = serde_json::to_string(bundle)?;
+        let txs: Vec<_> = bundle::txs(bundle)
+            .into_iter()
+            .map(|raw| {
+                let (hash, sender, nonce) = decode_tx(raw);
+                (
+                    format!("{:?}", hash),
+                    sender.map(|s| format!("{:?}", s)),
+                    nonce.map(|n| n.as_u64()),
+                    format!("{}", raw),
+                )
+            })
+            .collect();
+        self.write(move |conn| {
+            let db_tx = conn.transaction()?;
+            db_tx.execute(
+                "INSERT INTO bundles
+                 (id, event_hash, challenge, target_block, created_at, outcome, raw)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    bundle_id,
+                    event_hash,
+                    challenge,
+                    target_block,
+                    created_at,
+                    Outcome::Queued.as_str(),
+                    raw,
+                ],
+            )?;
+            for (idx, (hash, sender, nonce, raw)) in txs.into_iter().enumerate() {
+                db_tx.execute(
+                    "INSERT INTO bundle_txs (bundle_id, idx, hash, sender, nonce, raw)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![bundle_id, idx, hash, sender, nonce, raw],
+                )?;
+            }
+            db_tx.commit()
+        })?;
+        Ok(bundle_id)
+    }
+
+    pub fn record_relay_response(
+        &self,
+        bundle_id: i64,
+        relay: &str,
+        result: &Result<SendBundleResponse, RpcError>,
+    ) -> Result<()> {
+        let (ok, response) = match result {
+            Ok(resp) => (true, serde_json::to_string(resp)?),
+            Err(e) => (false, e.to_string()),
+        };
+        let relay = relay.to_string();
+        let received_at = now();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT INTO relay_responses (bundle_id, relay, ok, response, received_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![bundle_id, relay, ok, response, received_at],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn set_outcome(&self, bundle_id: i64, outcome: Outcome) -> Result<()> {
+        self.write(move |conn| {
+            conn.execute(
+                "UPDATE bundles SET outcome = ?1 WHERE id = ?2",
+                params![outcome.as_str(), bundle_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Most recent bundles first, optionally only for one challenge (`Flag::name`).
+    pub fn recent_attempts(&self, challenge: Option<&str>, limit: usize) -> Result<Vec<Attempt>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.challenge, b.event_hash, b.target_block, b.outcome, b.created_at,
+                    (SELECT COUNT(*) FROM bundle_txs t WHERE t.bundle_id = b.id)
+             FROM bundles b
+             WHERE ?1 IS NULL OR b.challenge = ?1
+             ORDER BY b.id DESC
+             LIMIT ?2",
+        )?;
+        let attempts = stmt
+            .query_map(params![challenge, limit], |row| {
+                Ok(Attempt {
+                    id: row.get(0)?,
+                    challenge: row.get(1)?,
+                    event_hash: row.get(2)?,
+                    target_block: row.get(3)?,
+                    outcome: row.get(4)?,
+                    created_at: row.get(5)?,
+                    txs: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(attempts)
+    }
+
+    /// Idempotent, the same log can come from both the backfill and the subscription.
+    pub fn record_capture(&self, capture: &Capture) -> Result<()> {
+        let tx_hash = format!("{:?}", capture.tx_hash);
+        let log_index = capture.log_index;
+        let contract = format!("{:?}", capture.contract);
+        let kind = capture.kind.map(|kind| kind.name());
+        let challenge = capture.flag.name();
+        let searcher = format!("{:?}", capture.searcher);
+        let block = capture.block;
+        let recorded_at = now();
+        self.write(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO captures
+                 (tx_hash, log_index, contract, kind, challenge, searcher, block, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    tx_hash,
+                    log_index,
+                    contract,
+                    kind,
+                    challenge,
+                    searcher,
+                    block,
+                    recorded_at
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Earliest capture of each flag, a challenge contract and kind.
+    pub fn captures(&self) -> Result<Vec<CaptureRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT contract, kind, challenge, searcher, MIN(block), tx_hash
+             FROM captures
+             GROUP BY contract, kind
+             ORDER BY challenge, contract, kind",
+        )?;
+        let captures = stmt
+            .query_map([], |row| {
+                Ok(CaptureRecord {
+                    contract: row.get(0)?,
+                    kind: row.get(1)?,
+                    challenge: row.get(2)?,
+                    searcher: row.get(3)?,
+                    block: row.get(4)?,
+                    tx_hash: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(captures)
+    }
+}
+
+/// Apply queued writes in order until every `Store` handle is gone.
+fn write_queued(mut conn: Connection, mut queued: mpsc::UnboundedReceiver<Write>) {
+    while let Some(write) = queued.blocking_recv() {
+        if let Err(e) = write(&mut conn) {
+            warn!("Failed to write to store {:?}", e);
+        }
+    }
+}
+
+/// hash, sender and nonce of a signed raw tx, sender/nonce are None if it doesn't decode
+fn decode_tx(raw: &Bytes) -> (H256, Option<Address>, Option<U256>) {
+    let hash = H256::from(keccak256(raw));
+    match TypedTransaction::decode_signed(&Rlp::new(raw)) {
+        Ok((tx, signature)) => (
+            hash,
+            signature.recover(tx.sighash()).ok(),
+            tx.nonce().copied(),
+        ),
+        Err(_) => (hash, None, None),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
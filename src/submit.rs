@@ -0,0 +1,451 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethers::prelude::{Middleware, Provider, Ws, H256, U64};
+use ethers::utils::keccak256;
+use eyre::{eyre, Result};
+use jsonrpsee::core::Error as RpcError;
+use mev_share::rpc::{
+    MevApiClient, SendBundleRequest, SendBundleResponse, SimBundleOverrides, SimBundleResponse,
+};
+use tokio::sync::{oneshot, watch, Mutex, Notify, Semaphore};
+use tokio::time::{sleep_until, Instant};
+use tracing::{info, info_span, warn, Instrument, Span};
+
+use crate::bundle::{self, BundleSettings};
+use crate::ctf::Flag;
+use crate::metrics;
+use crate::store::{Outcome, Store};
+use crate::telemetry;
+
+/// A relay the queue submits every bundle to, spaced at least `min_interval` apart.
+pub struct Relay<C> {
+    pub name: String,
+    pub client: Arc<C>,
+    pub min_interval: Duration,
+}
+
+pub struct RelayResponse {
+    pub relay: String,
+    pub result: Result<SendBundleResponse, RpcError>,
+}
+
+pub enum SubmissionOutcome {
+    /// one response per relay the bundle reached before its target block passed
+    Sent(Vec<RelayResponse>),
+    /// target block passed while the bundle was waiting in the queue
+    Dropped,
+}
+
+/// Central queue all solvers push bundles and simulations through.
+/// Both are dispatched highest `Flag::priority` first (fifo within a priority),
+/// with at most `max_in_flight` running at once and per relay rate limits.
+/// `simulate_now` is the way around it for simulations that cannot wait their turn.
+pub struct SubmissionQueue<C> {
+    relays: Vec<RateLimited<C>>,
+    pending: Mutex<BinaryHeap<Pending>>,
+    notify: Notify,
+    in_flight: Arc<Semaphore>,
+    head: watch::Receiver<U64>,
+    seq: AtomicU64,
+    store: Arc<Store>,
+    settings: BundleSettings,
+    //for receipts of sent bundles, to record whether they landed
+    client: Arc<Provider<Ws>>,
+}
+
+struct RateLimited<C> {
+    relay: Relay<C>,
+    next_slot: Mutex<Instant>,
+}
+
+struct Pending {
+    priority: u8,
+    seq: u64,
+    flag: Flag,
+    bundle: SendBundleRequest,
+    //span of the solver that built it so submission shows up under the event
+    span: Span,
+    job: Job,
+}
+
+enum Job {
+    /// sent to every relay
+    Send {
+        bundle_id: Option<i64>,
+        reply: oneshot::Sender<SubmissionOutcome>,
+    },
+    /// simulated on the first relay
+    Simulate {
+        reply: oneshot::Sender<Result<SimBundleResponse>>,
+    },
+}
+
+impl<C> SubmissionQueue<C>
+where
+    C: MevApiClient + Send + Sync + 'static,
+{
+    /// `head` must track the latest block number, it decides when a bundle is stale
+    /// and when a sent one is checked for inclusion.
+    pub fn spawn(
+        relays: Vec<Relay<C>>,
+        max_in_flight: usize,
+        head: watch::Receiver<U64>,
+        store: Arc<Store>,
+        settings: BundleSettings,
+        client: Arc<Provider<Ws>>,
+    ) -> Arc<Self> {
+        let relays = relays
+            .into_iter()
+            .map(|relay| RateLimited {
+                relay,
+                next_slot: Mutex::new(Instant::now()),
+            })
+            .collect();
+        let queue = Arc::new(Self {
+            relays,
+            pending: Mutex::new(BinaryHeap::new()),
+            notify: Notify::new(),
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+            head,
+            seq: AtomicU64::new(0),
+            store,
+            settings,
+            client,
+        });
+        tokio::spawn(queue.clone().dispatch());
+        queue
+    }
+
+    /// Queue a bundle with the challenge's privacy and validity options filled in,
+    /// the receiver resolves once every relay has answered or the bundle is dropped.
+    pub async fn submit(
+        &self,
+        flag: Flag,
+        mut bundle: SendBundleRequest,
+    ) -> Result<oneshot::Receiver<SubmissionOutcome>> {
+        self.settings.apply(&flag, &mut bundle)?;
+        let (reply, rx) = oneshot::channel();
+        metrics::BUNDLES_BUILT
+            .with_label_values(&[flag.name()])
+            .inc();
+        let bundle_id = match self.store.record_bundle(&flag, &bundle) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                warn!("Failed to record {:?} bundle {:?}", flag, e);
+                None
+            }
+        };
+        self.push(flag, bundle, Job::Send { bundle_id, reply })
+            .await;
+        Ok(rx)
+    }
+
+    /// Simulate on the first relay, queued at `flag`'s priority alongside submissions.
+    pub async fn simulate(
+        &self,
+        flag: Flag,
+        bundle: SendBundleRequest,
+    ) -> Result<SimBundleResponse> {
+        let (reply, rx) = oneshot::channel();
+        self.push(flag, bundle, Job::Simulate { reply }).await;
+        rx.await
+            .map_err(|_| eyre!("simulation dropped by the queue"))?
+    }
+
+    /// Simulate on the first relay straight away, skipping the queue and the relay's rate limit,
+    /// for solvers with many candidates to check before their target block. Given up on once
+    /// the head reaches that block.
+    pub async fn simulate_now(&self, bundle: SendBundleRequest) -> Result<SimBundleResponse> {
+        let block = bundle.inclusion.block;
+        let mut head = self.head.clone();
+        let passed = async move {
+            //no more heads, nothing to give up on
+            if head.wait_for(|head| *head >= block).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        tokio::select! {
+            response = self.sim_bundle(bundle) => response,
+            _ = passed => Err(eyre!("target block passed while simulating")),
+        }
+    }
+
+    async fn push(&self, flag: Flag, bundle: SendBundleRequest, job: Job) {
+        let pending = Pending {
+            priority: flag.priority(),
+            seq: self.seq.fetch_add(1, AtomicOrdering::Relaxed),
+            flag,
+            bundle,
+            span: Span::current(),
+            job,
+        };
+        self.pending.lock().await.push(pending);
+        self.notify.notify_one();
+    }
+
+    pub fn settings(&self) -> &BundleSettings {
+        &self.settings
+    }
+
+    fn is_stale(&self, bundle: &SendBundleRequest) -> bool {
+        let deadline = bundle.inclusion.max_block.unwrap_or(bundle.inclusion.block);
+        deadline <= *self.head.borrow()
+    }
+
+    async fn dispatch(self: Arc<Self>) {
+        loop {
+            //permit first, so what is popped is the best of what is queued once a slot frees
+            let permit = self
+                .in_flight
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("submission semaphore closed");
+            let pending = loop {
+                let next = self.pending.lock().await.pop();
+                match next {
+                    Some(pending) => break pending,
+                    None => self.notify.notified().await,
+                }
+            };
+            let Pending {
+                flag,
+                bundle,
+                span,
+                job,
+                ..
+            } = pending;
+            if self.is_stale(&bundle) {
+                match job {
+                    Job::Send { bundle_id, reply } => {
+                        telemetry::log_bundle_dropped(&flag, &bundle, "stale in queue");
+                        self.record_outcome(&flag, bundle_id, &SubmissionOutcome::Dropped);
+                        let _ = reply.send(SubmissionOutcome::Dropped);
+                    }
+                    Job::Simulate { reply } => {
+                        let _ = reply.send(Err(eyre!("target block passed before simulating")));
+                    }
+                }
+                continue;
+            }
+            let queue = self.clone();
+            let block = bundle.inclusion.block.as_u64();
+            match job {
+                Job::Send { bundle_id, reply } => tokio::spawn(
+                    async move {
+                        let outcome = queue.send_to_relays(&flag, &bundle).await;
+                        queue.record_outcome(&flag, bundle_id, &outcome);
+                        let accepted = matches!(&outcome, SubmissionOutcome::Sent(responses)
+                            if responses.iter().any(|response| response.result.is_ok()));
+                        let _ = reply.send(outcome);
+                        drop(permit);
+                        if let (true, Some(bundle_id)) = (accepted, bundle_id) {
+                            queue.record_inclusion(bundle_id, &bundle).await;
+                        }
+                    }
+                    .instrument(info_span!(parent: &span, "submit", block)),
+                ),
+                Job::Simulate { reply } => tokio::spawn(
+                    async move {
+                        let _ = reply.send(queue.send_simulation(bundle).await);
+                        drop(permit);
+                    }
+                    .instrument(span),
+                ),
+            };
+        }
+    }
+
+    /// Once the head reaches the bundle's last block, record whether our first tx in it landed.
+    /// Bundles sharing a nonce each get their own answer, only the one that landed has a receipt.
+    async fn record_inclusion(&self, bundle_id: i64, bundle: &SendBundleRequest) {
+        let first = bundle.inclusion.block;
+        let last = bundle.inclusion.max_block.unwrap_or(first);
+        if self
+            .head
+            .clone()
+            .wait_for(|head| *head >= last)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let Some(tx) = bundle::txs(bundle).first().copied() else {
+            return;
+        };
+        let hash = H256::from(keccak256(tx));
+        let receipt = match metrics::time_rpc(
+            "get_transaction_receipt",
+            self.client.get_transaction_receipt(hash),
+        )
+        .await
+        {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                warn!("Failed to check bundle inclusion {:?}", e);
+                return;
+            }
+        };
+        let landed_in = receipt.and_then(|receipt| receipt.block_number);
+        let outcome = match landed_in {
+            Some(block) if block >= first && block <= last => {
+                info!(%block, "Bundle included");
+                Outcome::Included
+            }
+            _ => {
+                info!(%last, "Bundle not included");
+                Outcome::NotIncluded
+            }
+        };
+        if let Err(e) = self.store.set_outcome(bundle_id, outcome) {
+            warn!("Failed to record bundle inclusion {:?}", e);
+        }
+    }
+
+    async fn send_simulation(&self, bundle: SendBundleRequest) -> Result<SimBundleResponse> {
+        if let Some(limited) = self.relays.first() {
+            limited.wait_for_slot().await;
+        }
+        self.sim_bundle(bundle).await
+    }
+
+    async fn sim_bundle(&self, bundle: SendBundleRequest) -> Result<SimBundleResponse> {
+        let limited = self
+            .relays
+            .first()
+            .ok_or_else(|| eyre!("no relay to simulate on"))?;
+        let response = metrics::time_rpc(
+            "mev_simBundle",
+            limited
+                .relay
+                .client
+                .sim_bundle(bundle, SimBundleOverrides::default()),
+        )
+        .instrument(info_span!("simulate", relay = %limited.relay.name))
+        .await?;
+        Ok(response)
+    }
+
+    async fn send_to_relays(&self, flag: &Flag, bundle: &SendBundleRequest) -> SubmissionOutcome {
+        let mut responses = Vec::new();
+        for limited in &self.relays {
+            limited.wait_for_slot().await;
+            //rate limit wait can outlast the target block
+            if self.is_stale(bundle) {
+                telemetry::log_bundle_dropped(flag, bundle, "stale waiting on relay");
+                break;
+            }
+            if responses.is_empty() {
+                metrics::bundle_sending(flag, bundle);
+            }
+            telemetry::log_bundle_sending(flag, &limited.relay.name, bundle);
+            let result = limited
+                .relay
+                .client
+                .send_bundle(bundle.clone())
+                .instrument(info_span!("relay", name = %limited.relay.name))
+                .await;
+            telemetry::log_relay_response(flag, &limited.relay.name, &result);
+            responses.push(RelayResponse {
+                relay: limited.relay.name.clone(),
+                result,
+            });
+        }
+        if responses.is_empty() {
+            SubmissionOutcome::Dropped
+        } else {
+            SubmissionOutcome::Sent(responses)
+        }
+    }
+}
+
+impl<C> SubmissionQueue<C> {
+    fn record_outcome(&self, flag: &Flag, bundle_id: Option<i64>, outcome: &SubmissionOutcome) {
+        let challenge = flag.name();
+        match outcome {
+            SubmissionOutcome::Dropped => metrics::BUNDLES_DROPPED
+                .with_label_values(&[challenge])
+                .inc(),
+            SubmissionOutcome::Sent(responses) => {
+                for response in responses {
+                    let counter = if response.result.is_ok() {
+                        &metrics::BUNDLES_SENT
+                    } else {
+                        &metrics::BUNDLES_FAILED
+                    };
+                    counter
+                        .with_label_values(&[&response.relay, challenge])
+                        .inc();
+                }
+            }
+        }
+
+        let Some(bundle_id) = bundle_id else {
+            return;
+        };
+        let res = match outcome {
+            SubmissionOutcome::Dropped => self.store.set_outcome(bundle_id, Outcome::Dropped),
+            SubmissionOutcome::Sent(responses) => {
+                let mut accepted = false;
+                for response in responses {
+                    accepted |= response.result.is_ok();
+                    if let Err(e) = self.store.record_relay_response(
+                        bundle_id,
+                        &response.relay,
+                        &response.result,
+                    ) {
+                        warn!("Failed to record relay response {:?}", e);
+                    }
+                }
+                let outcome = if accepted {
+                    Outcome::Sent
+                } else {
+                    Outcome::Rejected
+                };
+                self.store.set_outcome(bundle_id, outcome)
+            }
+        };
+        if let Err(e) = res {
+            warn!("Failed to record {:?} bundle outcome {:?}", flag, e);
+        }
+    }
+}
+
+impl<C> RateLimited<C> {
+    async fn wait_for_slot(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.relay.min_interval;
+            slot
+        };
+        sleep_until(slot).await;
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        //max-heap: higher priority first, then older submissions first
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl Eq for Pending {}
@@ -0,0 +1,141 @@
+use ethers::types::{Address, H256};
+use ethers::utils::{keccak256, to_checksum};
+use eyre::Result;
+use jsonrpsee::core::Error as RpcError;
+use mev_share::rpc::{SendBundleRequest, SendBundleResponse};
+use mev_share::sse::Event;
+use tracing::{info, warn};
+use tracing_subscriber::{filter::EnvFilter, fmt, prelude::*};
+
+use crate::bundle;
+use crate::config::LogFormat;
+use crate::ctf::Flag;
+
+/// Install the global subscriber, `LogFormat::Json` switches to one json object per line.
+/// With the `otlp` feature and an endpoint set, spans are also exported to that collector.
+pub fn init(format: LogFormat, otlp_endpoint: Option<&str>) -> Result<()> {
+    let json = format == LogFormat::Json;
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with((!json).then(fmt::layer))
+        .with(json.then(|| {
+            fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+        }));
+
+    #[cfg(feature = "otlp")]
+    {
+        if let Some(endpoint) = otlp_endpoint {
+            registry.with(otlp::layer(endpoint)?).init();
+            tracing::info!("Exporting spans to {}", endpoint);
+            return Ok(());
+        }
+    }
+    registry.init();
+    #[cfg(not(feature = "otlp"))]
+    if otlp_endpoint.is_some() {
+        warn!("OtlpEndpoint is set but the otlp feature is not compiled in");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use eyre::Result;
+    use opentelemetry::sdk::{trace, Resource};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    pub fn layer<S>(endpoint: &str) -> Result<OpenTelemetryLayer<S, trace::Tracer>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let resource = Resource::new(vec![KeyValue::new("service.name", "mevshare-ctf")]);
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(endpoint);
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(trace::config().with_resource(resource))
+            .install_batch(opentelemetry::runtime::Tokio)?;
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+//everything below logs with typed fields so the json output stays queryable,
+//hashes as full hex, addresses checksummed, blocks as integers
+
+pub fn hex(hash: &H256) -> String {
+    format!("{:?}", hash)
+}
+
+pub fn checksum(address: &Address) -> String {
+    to_checksum(address, None)
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(",")
+}
+
+pub fn log_event(event: &Event) {
+    info!(
+        hash = %hex(&event.hash),
+        txs = event.transactions.len(),
+        logs = event.logs.len(),
+        to = %join(event.transactions.iter().filter_map(|tx| tx.to.as_ref()).map(checksum)),
+        log_addresses = %join(event.logs.iter().map(|log| checksum(&log.address))),
+        topics = %join(event.logs.iter().filter_map(|log| log.topics.first()).map(hex)),
+        "event received"
+    );
+}
+
+pub fn log_bundle_sending(flag: &Flag, relay: &str, bundle: &SendBundleRequest) {
+    let target = bundle::target(bundle).map(|hash| hex(&hash));
+    let txs = bundle::txs(bundle);
+    info!(
+        challenge = flag.name(),
+        relay,
+        target = %target.unwrap_or_default(),
+        block = bundle.inclusion.block.as_u64(),
+        max_block = bundle.inclusion.max_block.map(|b| b.as_u64()),
+        txs = txs.len(),
+        tx_hashes = %join(txs.iter().map(|tx| hex(&H256::from(keccak256(tx))))),
+        "sending bundle"
+    );
+}
+
+pub fn log_relay_response(
+    flag: &Flag,
+    relay: &str,
+    result: &std::result::Result<SendBundleResponse, RpcError>,
+) {
+    match result {
+        Ok(resp) => info!(
+            challenge = flag.name(),
+            relay,
+            bundle_hash = %hex(&resp.bundle_hash),
+            "bundle accepted"
+        ),
+        Err(e) => warn!(
+            challenge = flag.name(),
+            relay,
+            error = %e,
+            "bundle rejected"
+        ),
+    }
+}
+
+pub fn log_bundle_dropped(flag: &Flag, bundle: &SendBundleRequest, reason: &str) {
+    warn!(
+        challenge = flag.name(),
+        block = bundle.inclusion.block.as_u64(),
+        reason,
+        "bundle dropped"
+    );
+}
@@ -0,0 +1,322 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ethers::prelude::*;
+use ethers::utils::format_ether;
+use eyre::{eyre, Result};
+use tokio::sync::{watch, Notify};
+use tracing::{info, instrument, warn};
+
+use crate::bid::Bid;
+use crate::metrics;
+use crate::signer::BotSigner;
+use crate::telemetry;
+
+/// Searcher wallets leased to one solve at a time while it signs, so solves in the same block
+/// spread over them rather than queueing on one.
+pub struct WalletPool {
+    wallets: Vec<Wallet>,
+    next: AtomicUsize,
+    released: Notify,
+    min_balance: U256,
+    low_balance_alert: U256,
+    head: watch::Receiver<U64>,
+}
+
+struct Wallet {
+    signer: BotSigner,
+    leased: AtomicBool,
+    funded: AtomicBool,
+    balance: Mutex<U256>,
+    //worst case cost of bundles that may still land, keyed by their last block
+    reserved: Mutex<Vec<(U64, U256)>>,
+    alerted: AtomicBool,
+    nonce: Mutex<NonceState>,
+}
+
+/// Fee caps a solve signs its txs with.
+#[derive(Debug, Clone, Copy)]
+pub struct Fees {
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+#[derive(Default, Clone, Copy)]
+struct NonceState {
+    synced_at: Option<U64>,
+    nonce: u64,
+}
+
+impl Wallet {
+    fn available(&self) -> U256 {
+        let reserved = self
+            .reserved
+            .lock()
+            .unwrap()
+            .iter()
+            .fold(U256::zero(), |sum, (_, cost)| sum + cost);
+        self.balance.lock().unwrap().saturating_sub(reserved)
+    }
+}
+
+impl WalletPool {
+    /// Fetch every balance once, then keep them fresh on each new head.
+    pub async fn spawn<M: Middleware + 'static>(
+        signers: Vec<BotSigner>,
+        min_balance: U256,
+        low_balance_alert: U256,
+        head: watch::Receiver<U64>,
+        client: Arc<M>,
+    ) -> Result<Arc<Self>> {
+        if signers.is_empty() {
+            return Err(eyre!("no wallets configured"));
+        }
+        let pool = Arc::new(Self {
+            wallets: signers
+                .into_iter()
+                .map(|signer| Wallet {
+                    signer,
+                    leased: AtomicBool::new(false),
+                    funded: AtomicBool::new(false),
+                    balance: Mutex::new(U256::zero()),
+                    reserved: Mutex::new(Vec::new()),
+                    alerted: AtomicBool::new(false),
+                    nonce: Mutex::new(NonceState::default()),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            released: Notify::new(),
+            min_balance,
+            low_balance_alert,
+            head: head.clone(),
+        });
+        pool.refresh_balances(client.as_ref()).await;
+
+        let monitor = pool.clone();
+        let mut head = head;
+        tokio::spawn(async move {
+            while head.changed().await.is_ok() {
+                monitor.refresh_balances(client.as_ref()).await;
+            }
+        });
+        Ok(pool)
+    }
+
+    async fn refresh_balances<M: Middleware>(&self, client: &M) {
+        let head = *self.head.borrow();
+        for wallet in &self.wallets {
+            let address = wallet.signer.address();
+            let balance = match metrics::time_rpc("get_balance", client.get_balance(address, None))
+                .await
+            {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!(address = %telemetry::checksum(&address), "Failed to fetch balance {:?}", e);
+                    continue;
+                }
+            };
+            *wallet.balance.lock().unwrap() = balance;
+            //bundles for passed blocks either landed and are in the balance or never will
+            wallet
+                .reserved
+                .lock()
+                .unwrap()
+                .retain(|(max_block, _)| *max_block > head);
+            self.check_low_balance(wallet, wallet.available());
+            metrics::WALLET_BALANCE
+                .with_label_values(&[&telemetry::checksum(&address)])
+                .set(format_ether(balance).parse().unwrap_or_default());
+
+            let funded = balance >= self.min_balance;
+            if wallet.funded.swap(funded, Ordering::SeqCst) != funded {
+                if funded {
+                    info!(address = %telemetry::checksum(&address), balance = %format_ether(balance), "Wallet funded, back in rotation");
+                } else {
+                    warn!(address = %telemetry::checksum(&address), balance = %format_ether(balance), "Wallet underfunded, excluded from rotation");
+                }
+            }
+        }
+    }
+
+    fn check_low_balance(&self, wallet: &Wallet, available: U256) {
+        let low = available < self.low_balance_alert;
+        if wallet.alerted.swap(low, Ordering::SeqCst) != low && low {
+            let address = wallet.signer.address();
+            metrics::LOW_BALANCE_ALERTS
+                .with_label_values(&[&telemetry::checksum(&address)])
+                .inc();
+            warn!(
+                alert = "low_balance",
+                address = %telemetry::checksum(&address),
+                available = %format_ether(available),
+                threshold = %format_ether(self.low_balance_alert),
+                "Wallet balance is low, top it up"
+            );
+        }
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.wallets
+            .iter()
+            .map(|wallet| wallet.signer.address())
+            .collect()
+    }
+
+    /// Take the next funded wallet nobody is using, waiting if they are all busy.
+    pub async fn lease(self: &Arc<Self>) -> Result<WalletLease> {
+        self.lease_where(|_| true).await
+    }
+
+    /// Like `lease` but only wallets whose address passes `allow`.
+    pub async fn lease_where(
+        self: &Arc<Self>,
+        allow: impl Fn(&Address) -> bool,
+    ) -> Result<WalletLease> {
+        loop {
+            //register before checking so a release in between is not missed
+            let released = self.released.notified();
+            if let Some(index) = self.try_lease(&allow) {
+                return Ok(WalletLease {
+                    pool: self.clone(),
+                    index,
+                });
+            }
+            if !self.wallets.iter().any(|wallet| {
+                wallet.funded.load(Ordering::SeqCst) && allow(&wallet.signer.address())
+            }) {
+                return Err(eyre!("no funded wallets"));
+            }
+            released.await;
+        }
+    }
+
+    fn try_lease(&self, allow: impl Fn(&Address) -> bool) -> Option<usize> {
+        let start = self.next.fetch_add(1, Ordering::SeqCst);
+        (0..self.wallets.len())
+            .map(|i| (start + i) % self.wallets.len())
+            .find(|&index| {
+                let wallet = &self.wallets[index];
+                wallet.funded.load(Ordering::SeqCst)
+                    && allow(&wallet.signer.address())
+                    && wallet
+                        .leased
+                        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                        .is_ok()
+            })
+    }
+}
+
+/// Exclusive use of one wallet, given back to the pool on drop.
+pub struct WalletLease {
+    pool: Arc<WalletPool>,
+    index: usize,
+}
+
+impl WalletLease {
+    pub fn signer(&self) -> &BotSigner {
+        &self.wallet().signer
+    }
+
+    /// Balance not already spoken for by in-flight bundles.
+    pub fn available(&self) -> U256 {
+        self.wallet().available()
+    }
+
+    /// Fees for a bundle of `gas` worth of txs plus whatever `bid` adds, all of which must be
+    /// paid for if it lands by `max_block`. The max fee is capped to what the wallet can afford,
+    /// refusing if that would fall below the current base fee plus tip, and the worst case cost
+    /// stays reserved until the head passes `max_block`.
+    #[instrument(name = "funding_check", skip(self, client), fields(address = %telemetry::checksum(&self.signer().address())))]
+    pub async fn fund<M: Middleware>(
+        &self,
+        client: &M,
+        gas: u64,
+        max_block: U64,
+        bid: &Bid,
+    ) -> Result<Fees>
+    where
+        M::Error: 'static,
+    {
+        let (mut max_fee, mut priority_fee) =
+            metrics::time_rpc("estimate_eip1559_fees", client.estimate_eip1559_fees(None)).await?;
+        if let Some(bid_fee) = bid.priority_fee {
+            //the estimate is base fee headroom plus its own tip, swap in ours
+            max_fee = max_fee.saturating_sub(priority_fee) + bid_fee;
+            priority_fee = bid_fee;
+        }
+        let gas = U256::from(gas + bid.extra_gas());
+        let transfer = bid.coinbase_transfer.unwrap_or_default();
+        let available = self.available();
+        if gas * max_fee + transfer > available {
+            let base_fee = metrics::time_rpc("get_block", client.get_block(BlockNumber::Latest))
+                .await?
+                .and_then(|block| block.base_fee_per_gas)
+                .unwrap_or_default();
+            let capped = available.saturating_sub(transfer) / gas;
+            if capped < base_fee + priority_fee {
+                self.pool.check_low_balance(self.wallet(), available);
+                return Err(eyre!(
+                    "wallet {} cannot pay for bundle, needs {} has {}",
+                    telemetry::checksum(&self.signer().address()),
+                    format_ether(gas * (base_fee + priority_fee) + transfer),
+                    format_ether(available)
+                ));
+            }
+            warn!(
+                max_fee = %max_fee,
+                capped = %capped,
+                "Capping max fee to the wallet balance"
+            );
+            max_fee = capped;
+        }
+        let cost = gas * max_fee + transfer;
+        self.wallet()
+            .reserved
+            .lock()
+            .unwrap()
+            .push((max_block, cost));
+        self.pool
+            .check_low_balance(self.wallet(), available.saturating_sub(cost));
+        Ok(Fees {
+            max_fee_per_gas: max_fee,
+            max_priority_fee_per_gas: priority_fee.min(max_fee),
+        })
+    }
+
+    /// First nonce of a bundle, the chain's count as of the latest block, read once per head.
+    /// Bundles are independent, any of them may land without the others, so each starts there
+    /// and only txs within one bundle take consecutive nonces after it.
+    #[instrument(name = "nonce_lookup", skip(self, client), fields(address = %telemetry::checksum(&self.signer().address())))]
+    pub async fn bundle_nonce<M: Middleware>(&self, client: &M) -> Result<u64>
+    where
+        M::Error: 'static,
+    {
+        let head = *self.pool.head.borrow();
+        let state = *self.wallet().nonce.lock().unwrap();
+        if state.synced_at == Some(head) {
+            return Ok(state.nonce);
+        }
+        let nonce = metrics::time_rpc(
+            "get_transaction_count",
+            client.get_transaction_count(self.signer().address(), None),
+        )
+        .await?
+        .as_u64();
+        *self.wallet().nonce.lock().unwrap() = NonceState {
+            synced_at: Some(head),
+            nonce,
+        };
+        Ok(nonce)
+    }
+
+    fn wallet(&self) -> &Wallet {
+        &self.pool.wallets[self.index]
+    }
+}
+
+impl Drop for WalletLease {
+    fn drop(&mut self) {
+        self.wallet().leased.store(false, Ordering::SeqCst);
+        self.pool.released.notify_waiters();
+    }
+}